@@ -0,0 +1,404 @@
+use alacritty_theme_switcher::{
+    formats::{self, ExportFormat, Format},
+    generate,
+    palette::Palette,
+    theme::{theme_name, FileFormat, Theme},
+    yaml,
+};
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use rand::seq::IndexedRandom;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    thread,
+    time::Duration,
+};
+
+use crate::ThemeChanger;
+
+// exit code used when the requested theme does not exist
+const THEME_NOT_FOUND: u8 = 2;
+
+// how often `schedule` checks whether the scheduled theme changed
+const SCHEDULE_INTERVAL: Duration = Duration::from_secs(60);
+
+impl ThemeChanger {
+    pub fn list(&mut self, filter: Option<&String>) -> Result<ExitCode> {
+        self.input = filter.cloned().unwrap_or_default();
+        self.update_matches();
+
+        for theme in self.get_matched_themes() {
+            println!("{}", theme.name);
+        }
+
+        Ok(ExitCode::SUCCESS)
+    }
+
+    pub fn current(&self) -> Result<ExitCode> {
+        match self.current_theme() {
+            Some(path) => {
+                // prefer the labelled name when the theme was found in the theme directories
+                let name = match self.store.find_path(&path) {
+                    Some(theme) => theme.name.clone(),
+                    None => theme_name(&path),
+                };
+                println!("{name}");
+                Ok(ExitCode::SUCCESS)
+            }
+            None => {
+                eprintln!("No theme is currently imported");
+                Ok(ExitCode::from(THEME_NOT_FOUND))
+            }
+        }
+    }
+
+    pub fn set(&mut self, name: &str) -> Result<ExitCode> {
+        match self.store.find(name) {
+            Some(theme) => self.apply_theme(theme.clone()),
+            None => {
+                eprintln!("Theme not found: {name}");
+                Ok(ExitCode::from(THEME_NOT_FOUND))
+            }
+        }
+    }
+
+    pub fn profiles(&self) -> Result<ExitCode> {
+        for profile in &self.profiles {
+            println!("{}", profile.name);
+        }
+
+        Ok(ExitCode::SUCCESS)
+    }
+
+    // imports the theme of the profile and sets its options
+    pub fn profile(&mut self, name: &str) -> Result<ExitCode> {
+        let Some(profile) = self.profiles.iter().find(|p| p.name == name).cloned() else {
+            eprintln!("Profile not found: {name}");
+            return Ok(ExitCode::from(THEME_NOT_FOUND));
+        };
+        let Some(theme) = self.store.find(&profile.theme).cloned() else {
+            eprintln!("Theme not found: {}", profile.theme);
+            return Ok(ExitCode::from(THEME_NOT_FOUND));
+        };
+
+        for problem in &theme.problems {
+            eprintln!("warning: {}: {problem}", theme.path.display());
+        }
+
+        self.config
+            .apply_profile(&self.store, &theme.path, &profile.overrides)?;
+        self.remember(&theme.path)?;
+
+        println!("{}", profile.name);
+
+        Ok(ExitCode::SUCCESS)
+    }
+
+    pub fn undo(&mut self) -> Result<ExitCode> {
+        match self.previous_theme() {
+            Some(theme) => self.apply_theme(theme),
+            None => {
+                eprintln!("No previously applied theme");
+                Ok(ExitCode::from(THEME_NOT_FOUND))
+            }
+        }
+    }
+
+    pub fn apply_scheduled(&mut self) -> Result<ExitCode> {
+        let name = self.scheduled_theme()?;
+        self.apply_named(&name)
+    }
+
+    pub fn schedule(&mut self) -> Result<ExitCode> {
+        // the theme is only imported when the schedule moves on, so a theme picked by hand in the
+        // meantime is kept until the next change
+        let mut last = None;
+
+        loop {
+            let name = self.scheduled_theme()?;
+            if last.as_ref() != Some(&name) {
+                // pick up the changes made to the config and the themes since the last import, the
+                // errors, e.g. with the config halfway through an edit, are retried on the next tick
+                let result = self.config.reload().and_then(|()| {
+                    self.store.rescan();
+                    self.apply_named(&name)
+                });
+                match result {
+                    Ok(_) => last = Some(name),
+                    Err(e) => eprintln!("Failed to apply {name}: {e}"),
+                }
+            }
+
+            thread::sleep(SCHEDULE_INTERVAL);
+        }
+    }
+
+    fn scheduled_theme(&self) -> Result<String> {
+        let schedule = self
+            .schedule
+            .as_ref()
+            .ok_or_else(|| anyhow!("No [schedule] table in the settings file"))?;
+
+        schedule
+            .theme_at(Local::now())
+            .map(String::from)
+            .ok_or_else(|| anyhow!("No theme is scheduled"))
+    }
+
+    // imports the theme with the given name, unless it is already imported
+    fn apply_named(&mut self, name: &str) -> Result<ExitCode> {
+        let Some(theme) = self.store.find(name).cloned() else {
+            eprintln!("Theme not found: {name}");
+            return Ok(ExitCode::from(THEME_NOT_FOUND));
+        };
+
+        if self.current_theme().as_ref() == Some(&theme.path) {
+            println!("{}", theme.name);
+            return Ok(ExitCode::SUCCESS);
+        }
+
+        self.apply_theme(theme)
+    }
+
+    pub fn random(&mut self, filter: Option<&String>) -> Result<ExitCode> {
+        self.input = filter.cloned().unwrap_or_default();
+        self.update_matches();
+
+        // avoid picking the theme that is already imported
+        let current = self.current_theme();
+        let candidates: Vec<_> = self
+            .get_matched_themes()
+            .into_iter()
+            .filter(|t| Some(&t.path) != current.as_ref())
+            .collect();
+
+        match candidates.choose(&mut rand::rng()) {
+            Some(theme) => self.apply_theme(theme.clone()),
+            None => {
+                eprintln!("No theme matches the filter");
+                Ok(ExitCode::from(THEME_NOT_FOUND))
+            }
+        }
+    }
+
+    pub fn lint(&self) -> Result<ExitCode> {
+        let mut count = 0;
+
+        for theme in &self.store.themes {
+            for problem in &theme.problems {
+                println!("{}: {problem}", theme.path.display());
+                count += 1;
+            }
+        }
+
+        if count == 0 {
+            println!("No problems found in {} themes", self.store.themes.len());
+            return Ok(ExitCode::SUCCESS);
+        }
+
+        let themes = self
+            .store
+            .themes
+            .iter()
+            .filter(|t| !t.problems.is_empty())
+            .count();
+        eprintln!("{count} problems found in {themes} themes");
+
+        Ok(ExitCode::FAILURE)
+    }
+
+    fn apply_theme(&mut self, theme: Theme) -> Result<ExitCode> {
+        // the theme is imported anyway, alacritty skips the invalid options
+        for problem in &theme.problems {
+            eprintln!("warning: {}: {problem}", theme.path.display());
+        }
+
+        self.config.import_theme(&self.store, &theme.path)?;
+        self.remember(&theme.path)?;
+
+        println!("{}", theme.name);
+
+        Ok(ExitCode::SUCCESS)
+    }
+
+    pub fn import(
+        &self,
+        file: &Path,
+        format: Option<Format>,
+        name: Option<&String>,
+        force: bool,
+    ) -> Result<ExitCode> {
+        let format = format
+            .or_else(|| Format::detect(file))
+            .ok_or_else(|| anyhow!("Unknown theme format, use --format to specify it"))?;
+
+        let schemes = formats::import(file, format)?;
+        if name.is_some() && schemes.len() > 1 {
+            return Err(anyhow!(
+                "--name can't be used with a file containing several schemes"
+            ));
+        }
+
+        for scheme in schemes {
+            let scheme_name = name
+                .cloned()
+                .or(scheme.name)
+                .unwrap_or_else(|| theme_name(file));
+
+            let path = self.write_theme(&scheme_name, &scheme.palette, force)?;
+            println!("Imported {scheme_name} to {}", path.display());
+
+            if !scheme.unmapped.is_empty() {
+                println!("  not mapped: {}", scheme.unmapped.join(", "));
+            }
+
+            let missing = scheme.palette.missing();
+            if !missing.is_empty() {
+                println!("  missing: {}", missing.join(", "));
+            }
+        }
+
+        Ok(ExitCode::SUCCESS)
+    }
+
+    pub fn generate(&self, image: &Path, name: Option<&String>, force: bool) -> Result<ExitCode> {
+        let palette = generate::from_image(image)
+            .map_err(|e| anyhow!("Failed to read {}: {e}", image.display()))?;
+
+        let name = name.cloned().unwrap_or_else(|| theme_name(image));
+        let path = self.write_theme(&name, &palette, force)?;
+        println!("Generated {name} to {}", path.display());
+
+        if let Some((contrast, color)) = palette.min_contrast() {
+            println!("  minimum contrast: {contrast:.1} ({color})");
+        }
+
+        Ok(ExitCode::SUCCESS)
+    }
+
+    // writes a new theme to the first theme directory, so it shows up in the list
+    fn write_theme(&self, name: &str, palette: &Palette, force: bool) -> Result<PathBuf> {
+        self.store.check_writable()?;
+        let themes_dir = self
+            .store
+            .dirs
+            .first()
+            .ok_or_else(|| anyhow!("No theme directory to write the theme to"))?;
+        fs::create_dir_all(themes_dir)?;
+
+        let path = themes_dir.join(format!("{}.toml", formats::file_name(name)));
+        if path.exists() && !force {
+            return Err(anyhow!(
+                "{} already exists, use --force to overwrite it",
+                path.display()
+            ));
+        }
+
+        fs::write(&path, palette.to_toml())?;
+
+        Ok(path)
+    }
+
+    // converts the YAML config, the YAML files it imports and the YAML themes to TOML, next to them,
+    // the YAML files are kept
+    pub fn migrate(&self, force: bool) -> Result<ExitCode> {
+        if self.config.format != FileFormat::Yaml {
+            return Err(anyhow!(
+                "{} is already a TOML config",
+                self.config.path.display()
+            ));
+        }
+
+        // the config is checked first, so nothing is written when it can't be migrated
+        let config_target = self.config.path.with_extension("toml");
+        if config_target.exists() && !force {
+            return Err(anyhow!(
+                "{} already exists, use --force to overwrite it",
+                config_target.display()
+            ));
+        }
+
+        let config_doc = yaml::migrate(&self.config.contents)
+            .map_err(|e| anyhow!("Failed to migrate {}: {e}", self.config.path.display()))?;
+
+        let mut files: Vec<_> = self.store.themes.iter().map(|t| t.path.clone()).collect();
+
+        for import in yaml::imports(&self.config.contents) {
+            let path = self.config.resolve_import(&import);
+            if FileFormat::of(&path) == Some(FileFormat::Yaml)
+                && path.is_file()
+                && !files.contains(&path)
+            {
+                files.push(path);
+            }
+        }
+
+        for file in files {
+            let target = file.with_extension("toml");
+            if target.exists() && !force {
+                println!(
+                    "Skipped {}, {} already exists",
+                    file.display(),
+                    target.display()
+                );
+                continue;
+            }
+
+            let contents = fs::read_to_string(&file)?;
+            let doc = yaml::migrate(&contents)
+                .map_err(|e| anyhow!("Failed to migrate {}: {e}", file.display()))?;
+            fs::write(&target, doc.to_string())?;
+            println!("Migrated {} to {}", file.display(), target.display());
+        }
+
+        fs::write(&config_target, config_doc.to_string())?;
+        println!(
+            "Migrated {} to {}",
+            self.config.path.display(),
+            config_target.display()
+        );
+        println!("The YAML files are kept, alacritty 0.13 and later read the TOML config first");
+
+        Ok(ExitCode::SUCCESS)
+    }
+
+    pub fn export(
+        &self,
+        name: &str,
+        format: ExportFormat,
+        output: Option<&PathBuf>,
+    ) -> Result<ExitCode> {
+        let Some(theme) = self.store.find(name) else {
+            eprintln!("Theme not found: {name}");
+            return Ok(ExitCode::from(THEME_NOT_FOUND));
+        };
+
+        let palette = theme
+            .palette
+            .as_ref()
+            .ok_or_else(|| anyhow!("Failed to read {}", theme.path.display()))?;
+
+        let contents = formats::export(&theme.name, palette, format)?;
+
+        let path = match output {
+            Some(output) => output.clone(),
+            None => PathBuf::from(format!(
+                "{}.{}",
+                formats::file_name(&theme.name),
+                format.extension()
+            )),
+        };
+
+        fs::write(&path, contents)?;
+        println!("Exported {} to {}", theme.name, path.display());
+
+        let missing = palette.missing();
+        if !missing.is_empty() {
+            println!("  missing: {}", missing.join(", "));
+        }
+
+        Ok(ExitCode::SUCCESS)
+    }
+}
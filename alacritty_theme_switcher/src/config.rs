@@ -0,0 +1,658 @@
+use anyhow::{anyhow, Result};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+use toml_edit::{value, Array, DocumentMut, Item, Table, TableLike};
+
+use crate::{
+    config_file::{backup, write_atomic},
+    profile::Override,
+    settings::expand_home,
+    store::ThemeStore,
+    theme::FileFormat,
+    yaml,
+};
+
+// environment variable giving the config file to use instead of the one alacritty would find
+pub const CONFIG_VAR: &str = "ALACRITTY_CONFIG";
+
+// the config is the one given on the command line or in the environment, otherwise alacritty looks
+// for a TOML config in every location before falling back to the YAML config of versions before
+// 0.13
+pub fn find_config(requested: Option<&Path>) -> Result<PathBuf> {
+    let requested = requested.map(Path::to_path_buf).or_else(|| {
+        env::var(CONFIG_VAR)
+            .ok()
+            .filter(|path| !path.is_empty())
+            .map(|path| expand_home(&path))
+    });
+
+    if let Some(path) = requested {
+        if !path.is_file() {
+            return Err(anyhow!("Config file not found: {}", path.display()));
+        }
+        // the relative imports and theme directories must not depend on the working directory
+        return Ok(std::path::absolute(path)?);
+    }
+
+    find_config_named("alacritty.toml")
+        .or_else(|| find_config_named("alacritty.yml"))
+        .ok_or_else(|| anyhow!("Failed to find config file"))
+}
+
+// function adapted from alacritty's source
+// https://github.com/alacritty/alacritty/blob/6fefa78eafa43f13998439cb9eaf15bc0441f004/alacritty/src/config/mod.rs#L378
+fn find_config_named(file_name: &str) -> Option<PathBuf> {
+    xdg::BaseDirectories::with_prefix("alacritty")
+        .ok()
+        .and_then(|xdg| xdg.find_config_file(file_name))
+        .or_else(|| {
+            xdg::BaseDirectories::new()
+                .ok()
+                .and_then(|fallback| fallback.find_config_file(file_name))
+        })
+        .or_else(|| {
+            if let Ok(home) = env::var("HOME") {
+                // Fallback path: $HOME/.config/alacritty/alacritty.toml.
+                let fallback = PathBuf::from(&home)
+                    .join(".config/alacritty")
+                    .join(file_name);
+                if fallback.exists() {
+                    return Some(fallback);
+                }
+                // Fallback path: $HOME/.alacritty.toml.
+                let hidden_name = format!(".{file_name}");
+                let fallback = PathBuf::from(&home).join(hidden_name);
+                if fallback.exists() {
+                    return Some(fallback);
+                }
+            }
+            None
+        })
+}
+
+// the alacritty config file, whose theme import is rewritten while the original contents are kept
+#[derive(Debug, Default)]
+pub struct Config {
+    pub path: PathBuf,            // Path to the config file.
+    pub format: FileFormat,       // Format of the config file, YAML for the legacy configs.
+    pub contents: String,         // Contents of the config file, as it was read.
+    pub document: DocumentMut,    // Document of the contents, converted to TOML for YAML configs.
+    backup_path: Option<PathBuf>, // The copy of the config made before changing it.
+    on_disk: Option<String>,      // The contents last written, or found by a reload.
+}
+
+impl Config {
+    pub fn read(path: PathBuf) -> Result<Config> {
+        let mut config = Config {
+            format: FileFormat::of(&path).unwrap_or_default(),
+            path,
+            ..Config::default()
+        };
+        config.reload()?;
+
+        Ok(config)
+    }
+
+    // reads the file again, to pick up the changes made since it was read
+    pub fn reload(&mut self) -> Result<()> {
+        let contents = fs::read_to_string(&self.path)?;
+        self.parse(contents)?;
+        self.on_disk = None;
+
+        Ok(())
+    }
+
+    fn parse(&mut self, contents: String) -> Result<()> {
+        self.document = match self.format {
+            FileFormat::Toml => contents.parse()?,
+            FileFormat::Yaml => yaml::to_toml(&contents)?,
+        };
+        self.contents = contents;
+
+        Ok(())
+    }
+
+    // reads the file again when something else, such as an editor, changed it, returns whether it
+    // did, the theme imported when the file was first read is kept, so restoring the config keeps
+    // the other changes but not the previewed theme
+    pub fn reload_if_changed(&mut self, store: &ThemeStore) -> Result<bool> {
+        let contents = fs::read_to_string(&self.path)?;
+        if contents == *self.on_disk.as_ref().unwrap_or(&self.contents) {
+            return Ok(false);
+        }
+
+        let original = self.current_theme(store);
+        self.parse(contents.clone())?;
+        if let Some(theme) = original.filter(|t| self.current_theme(store).as_ref() != Some(t)) {
+            let restored = self.with_theme_import(store, &theme)?;
+            self.parse(restored)?;
+        }
+        self.on_disk = Some(contents);
+
+        Ok(true)
+    }
+
+    // returns the `import` array, which is at the top level of the YAML configs
+    fn imports(&self) -> Option<&Item> {
+        match self.format {
+            FileFormat::Toml => self.document.get("general")?.get("import"),
+            FileFormat::Yaml => self.document.get("import"),
+        }
+    }
+
+    // resolves an import like alacritty, `~` and `$HOME` are expanded and relative paths are
+    // relative to the config file
+    pub fn resolve_import(&self, entry: &str) -> PathBuf {
+        let config_dir = self.path.parent().unwrap_or(Path::new(""));
+        config_dir.join(expand_home(entry))
+    }
+
+    // returns the theme imported by the config file as it was read
+    pub fn current_theme(&self, store: &ThemeStore) -> Option<PathBuf> {
+        self.imports().and_then(Item::as_array).and_then(|import| {
+            import
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(|s| self.resolve_import(s))
+                .find(|path| store.is_theme_import(path))
+        })
+    }
+
+    // returns whether an import entry of the config points to a theme
+    fn is_theme_import(&self, store: &ThemeStore, entry: &str) -> bool {
+        store.is_theme_import(&self.resolve_import(entry))
+    }
+
+    // returns the contents of the config with the given theme imported
+    pub fn with_theme_import(&self, store: &ThemeStore, theme: &Path) -> Result<String> {
+        let theme = theme.to_string_lossy().to_string();
+
+        if self.format == FileFormat::Yaml {
+            let is_theme = |s: &str| self.is_theme_import(store, s);
+            return yaml::with_theme_import(&self.contents, is_theme, &theme);
+        }
+
+        // clone to avoid mutating the original
+        let mut config_clone = self.document.clone();
+
+        // ensure the general section exists
+        if !config_clone.contains_key("general") {
+            config_clone.insert("general", Item::Table(Table::new()));
+        }
+
+        // retrieve the general section
+        let general = config_clone["general"]
+            .as_table_like_mut()
+            .ok_or_else(|| anyhow!("`general` is not a table"))?;
+
+        // ensure the import array exists in the general section
+        if !general.contains_key("import") {
+            general.insert("import", value(Array::new()));
+        }
+
+        // retrieve the import array from the general section
+        let import = general
+            .get_mut("import")
+            .and_then(Item::as_array_mut)
+            .ok_or_else(|| anyhow!("`general.import` is not an array"))?;
+
+        // find the entry that currently imports a theme, leaving every other import untouched
+        let position = import
+            .iter()
+            .position(|v| v.as_str().is_some_and(|s| self.is_theme_import(store, s)));
+
+        match position {
+            Some(index) => {
+                // replace keeps the surrounding whitespace and comments of the entry
+                import.replace(index, theme);
+            }
+            None => {
+                import.push(theme);
+            }
+        }
+
+        Ok(config_clone.to_string())
+    }
+
+    // returns the contents of the config with the given theme imported and the options of a
+    // profile set, the YAML configs only support profiles without options
+    pub fn with_profile(
+        &self,
+        store: &ThemeStore,
+        theme: &Path,
+        overrides: &[Override],
+    ) -> Result<String> {
+        let contents = self.with_theme_import(store, theme)?;
+        if overrides.is_empty() {
+            return Ok(contents);
+        }
+        if self.format == FileFormat::Yaml {
+            return Err(anyhow!(
+                "Profiles setting options need a TOML config, see `migrate`"
+            ));
+        }
+
+        let mut doc: DocumentMut = contents.parse()?;
+        for (key, value) in overrides {
+            set_option(&mut doc, key, value)?;
+        }
+
+        Ok(doc.to_string())
+    }
+
+    // writes the config with the given theme imported and the options of a profile set
+    pub fn apply_profile(
+        &mut self,
+        store: &ThemeStore,
+        theme: &Path,
+        overrides: &[Override],
+    ) -> Result<()> {
+        let config = self.with_profile(store, theme, overrides)?;
+        self.write(&config)
+    }
+
+    // writes the config with the given theme imported
+    pub fn import_theme(&mut self, store: &ThemeStore, theme: &Path) -> Result<()> {
+        let config = self.with_theme_import(store, theme)?;
+        self.write(&config)
+    }
+
+    // writes the config atomically, after keeping a backup of the original the first time
+    pub fn write(&mut self, contents: &str) -> Result<()> {
+        if self.backup_path.is_none() {
+            self.backup_path = Some(backup(&self.path)?);
+        }
+
+        write_atomic(&self.path, contents)?;
+        self.on_disk = Some(contents.to_string());
+
+        Ok(())
+    }
+
+    // writes back the file as it was read, if it was changed
+    pub fn restore(&mut self) -> Result<()> {
+        // nothing to restore if the config was never written
+        if self.backup_path.is_none() {
+            return Ok(());
+        }
+
+        let original = self.contents.clone();
+        self.write(&original)
+    }
+}
+
+// sets an option, creating the tables leading to it, the comments around an existing value are
+// kept
+fn set_option(doc: &mut DocumentMut, key: &[String], value: &toml_edit::Value) -> Result<()> {
+    let (name, tables) = key
+        .split_last()
+        .ok_or_else(|| anyhow!("Empty option name"))?;
+
+    let mut table: &mut dyn TableLike = doc.as_table_mut();
+    for (depth, table_name) in tables.iter().enumerate() {
+        if !table.contains_key(table_name) {
+            let mut new = Table::new();
+            new.set_implicit(true);
+            table.insert(table_name, Item::Table(new));
+        }
+        table = table
+            .get_mut(table_name)
+            .and_then(Item::as_table_like_mut)
+            .ok_or_else(|| anyhow!("`{}` is not a table", tables[..=depth].join(".")))?;
+    }
+
+    let mut value = value.clone();
+    if let Some(old) = table.get(name).and_then(Item::as_value) {
+        *value.decor_mut() = old.decor().clone();
+    }
+    table.insert(name, Item::Value(value));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        ffi::{OsStr, OsString},
+        sync::{Mutex, MutexGuard, PoisonError},
+    };
+    use tempfile::TempDir;
+
+    // the locations depend on environment variables, which are shared by the tests
+    static ENV: Mutex<()> = Mutex::new(());
+
+    // holds the environment for a test, and puts back the variables it changed when dropped
+    struct Env {
+        saved: Vec<(&'static str, Option<OsString>)>, // The changed variables and their values.
+        _lock: MutexGuard<'static, ()>,               // The lock, released after restoring.
+    }
+
+    impl Env {
+        fn lock() -> Env {
+            Env {
+                saved: Vec::new(),
+                _lock: ENV.lock().unwrap_or_else(PoisonError::into_inner),
+            }
+        }
+
+        fn set(&mut self, key: &'static str, value: impl AsRef<OsStr>) {
+            self.saved.push((key, env::var_os(key)));
+            env::set_var(key, value);
+        }
+
+        fn remove(&mut self, key: &'static str) {
+            self.saved.push((key, env::var_os(key)));
+            env::remove_var(key);
+        }
+    }
+
+    impl Drop for Env {
+        fn drop(&mut self) {
+            for (key, value) in self.saved.drain(..).rev() {
+                match value {
+                    Some(value) => env::set_var(key, value),
+                    None => env::remove_var(key),
+                }
+            }
+        }
+    }
+
+    // runs `find_config` with a home directory holding the given files
+    fn find_config_in(files: &[&str]) -> (TempDir, Result<PathBuf>) {
+        let mut env = Env::lock();
+
+        let home = tempfile::tempdir().unwrap();
+        for file in files {
+            let path = home.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+
+        env.set("HOME", home.path());
+        env.set("XDG_CONFIG_HOME", home.path().join(".config"));
+        env.set("XDG_CONFIG_DIRS", home.path().join("etc/xdg"));
+        env.remove(CONFIG_VAR);
+        let config = find_config(None);
+
+        (home, config)
+    }
+
+    #[test]
+    fn find_config_in_alacritty_dir() {
+        let (home, config) = find_config_in(&[
+            ".config/alacritty/alacritty.toml",
+            ".config/alacritty.toml",
+            ".alacritty.toml",
+        ]);
+
+        assert_eq!(
+            config.unwrap(),
+            home.path().join(".config/alacritty/alacritty.toml")
+        );
+    }
+
+    #[test]
+    fn find_config_in_config_dirs() {
+        let (home, config) = find_config_in(&["etc/xdg/alacritty/alacritty.toml"]);
+
+        assert_eq!(
+            config.unwrap(),
+            home.path().join("etc/xdg/alacritty/alacritty.toml")
+        );
+    }
+
+    #[test]
+    fn find_hidden_config_in_home() {
+        let (home, config) = find_config_in(&[".alacritty.toml"]);
+
+        assert_eq!(config.unwrap(), home.path().join(".alacritty.toml"));
+    }
+
+    #[test]
+    fn find_legacy_config() {
+        let (home, config) = find_config_in(&[".config/alacritty/alacritty.yml"]);
+
+        assert_eq!(
+            config.unwrap(),
+            home.path().join(".config/alacritty/alacritty.yml")
+        );
+    }
+
+    #[test]
+    fn prefer_toml_config() {
+        let (home, config) =
+            find_config_in(&[".config/alacritty/alacritty.yml", ".alacritty.toml"]);
+
+        assert_eq!(config.unwrap(), home.path().join(".alacritty.toml"));
+    }
+
+    #[test]
+    fn missing_config() {
+        let (_home, config) = find_config_in(&["alacritty.toml"]);
+
+        assert!(config.is_err());
+    }
+
+    #[test]
+    fn find_requested_config() {
+        let mut env = Env::lock();
+
+        let dir = tempfile::tempdir().unwrap();
+        let scratch = dir.path().join("scratch.toml");
+        let profile = dir.path().join("profile.toml");
+        fs::write(&scratch, "").unwrap();
+        fs::write(&profile, "").unwrap();
+
+        env.set(CONFIG_VAR, &profile);
+        assert_eq!(find_config(None).unwrap(), profile);
+        assert_eq!(find_config(Some(&scratch)).unwrap(), scratch);
+        assert!(find_config(Some(&dir.path().join("missing.toml"))).is_err());
+    }
+
+    #[test]
+    fn resolve_imports_like_alacritty() {
+        let mut env = Env::lock();
+
+        let dir = tempfile::tempdir().unwrap();
+        let themes = dir.path().join("themes");
+        let store = ThemeStore::new(vec![themes.clone()], FileFormat::Toml);
+        env.set("HOME", dir.path());
+
+        for entry in [
+            "themes/dracula.toml",
+            "./themes/dracula.toml",
+            "~/themes/dracula.toml",
+            "$HOME/themes/dracula.toml",
+            "${HOME}/themes/dracula.toml",
+        ] {
+            let path = dir.path().join("alacritty.toml");
+            let contents = format!("[general]\nimport = [\"keys.toml\", \"{entry}\"]\n");
+            fs::write(&path, &contents).unwrap();
+
+            let config = Config::read(path).unwrap();
+            assert_eq!(
+                config.current_theme(&store),
+                Some(themes.join("dracula.toml")),
+                "{entry}"
+            );
+            assert_eq!(
+                config
+                    .with_theme_import(&store, &themes.join("nord.toml"))
+                    .unwrap(),
+                contents.replace(entry, &themes.join("nord.toml").to_string_lossy()),
+            );
+        }
+    }
+
+    #[test]
+    fn reject_malformed_imports() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ThemeStore::new(vec![dir.path().join("themes")], FileFormat::Toml);
+        let theme = dir.path().join("themes/nord.toml");
+
+        for contents in ["general = 1\n", "[general]\nimport = \"keys.toml\"\n"] {
+            let path = dir.path().join("alacritty.toml");
+            fs::write(&path, contents).unwrap();
+
+            let config = Config::read(path).unwrap();
+            assert!(
+                config.with_theme_import(&store, &theme).is_err(),
+                "{contents}"
+            );
+        }
+    }
+
+    #[test]
+    fn import_replaces_only_the_theme() {
+        // `~/keys.toml` is resolved with the home directory
+        let _env = Env::lock();
+
+        let dir = tempfile::tempdir().unwrap();
+        let themes = dir.path().join("themes");
+        let store = ThemeStore::new(vec![themes.clone()], FileFormat::Toml);
+
+        let path = dir.path().join("alacritty.toml");
+        let contents = format!(
+            "[general]\nimport = [\n  \"~/keys.toml\",\n  \"{}\", # theme\n]\n",
+            themes.join("dracula.toml").display()
+        );
+        fs::write(&path, &contents).unwrap();
+
+        let mut config = Config::read(path.clone()).unwrap();
+        config
+            .import_theme(&store, &themes.join("nord.toml"))
+            .unwrap();
+
+        let written = fs::read_to_string(&path).unwrap();
+        assert_eq!(written, contents.replace("dracula", "nord"));
+        assert_eq!(
+            fs::read_to_string(dir.path().join("alacritty.toml.bak")).unwrap(),
+            contents
+        );
+
+        // the document is still the original one
+        assert_eq!(
+            config.current_theme(&store),
+            Some(themes.join("dracula.toml"))
+        );
+
+        config.restore().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), contents);
+    }
+
+    #[test]
+    fn reload_external_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let themes = dir.path().join("themes");
+        let store = ThemeStore::new(vec![themes.clone()], FileFormat::Toml);
+
+        let path = dir.path().join("alacritty.toml");
+        let dracula = themes.join("dracula.toml").display().to_string();
+        let nord = themes.join("nord.toml").display().to_string();
+        fs::write(&path, format!("[general]\nimport = [\"{dracula}\"]\n")).unwrap();
+
+        let mut config = Config::read(path.clone()).unwrap();
+        assert!(!config.reload_if_changed(&store).unwrap());
+
+        // the previewed theme isn't a change
+        config.import_theme(&store, Path::new(&nord)).unwrap();
+        assert!(!config.reload_if_changed(&store).unwrap());
+
+        // an editor saves the config holding the previewed theme
+        let edited = format!("[general]\nimport = [\"{nord}\"]\n\n[font]\nsize = 11\n");
+        fs::write(&path, &edited).unwrap();
+        assert!(config.reload_if_changed(&store).unwrap());
+        assert!(!config.reload_if_changed(&store).unwrap());
+        assert_eq!(config.contents, edited.replace(&nord, &dracula));
+
+        config.restore().unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            edited.replace(&nord, &dracula)
+        );
+    }
+
+    #[test]
+    fn apply_profile_options() {
+        let dir = tempfile::tempdir().unwrap();
+        let themes = dir.path().join("themes");
+        let store = ThemeStore::new(vec![themes.clone()], FileFormat::Toml);
+
+        let path = dir.path().join("alacritty.toml");
+        let contents = "\
+[window]
+opacity = 0.9 # see-through
+padding = { x = 2, y = 2 }
+";
+        fs::write(&path, contents).unwrap();
+
+        let profiles = crate::profile::parse_profiles(
+            "[work]\ntheme = \"nord\"\nfont.size = 13\nwindow.opacity = 1.0\nwindow.padding.x = 12\n",
+        )
+        .unwrap();
+        let nord = themes.join("nord.toml");
+
+        let mut config = Config::read(path.clone()).unwrap();
+        config
+            .apply_profile(&store, &nord, &profiles[0].overrides)
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!(
+                "\
+[window]
+opacity = 1.0 # see-through
+padding = {{ x = 12, y = 2 }}
+
+[general]
+import = [\"{}\"]
+
+[font]
+size = 13
+",
+                nord.display()
+            )
+        );
+
+        config.restore().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), contents);
+    }
+
+    #[test]
+    fn import_into_yaml_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let themes = dir.path().join("themes");
+        let store = ThemeStore::new(vec![themes.clone()], FileFormat::Yaml);
+
+        let path = dir.path().join("alacritty.yml");
+        let contents = format!(
+            "# my config\nimport:\n  - {}\n\nfont:\n  size: 11\n",
+            themes.join("dracula.yml").display()
+        );
+        fs::write(&path, &contents).unwrap();
+
+        let mut config = Config::read(path.clone()).unwrap();
+        assert_eq!(config.format, FileFormat::Yaml);
+        assert_eq!(
+            config.current_theme(&store),
+            Some(themes.join("dracula.yml"))
+        );
+
+        config
+            .import_theme(&store, &themes.join("nord.yml"))
+            .unwrap();
+
+        let nord = format!("\"{}\"", themes.join("nord.yml").display());
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            contents.replace(&themes.join("dracula.yml").display().to_string(), &nord)
+        );
+
+        config.restore().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), contents);
+    }
+}
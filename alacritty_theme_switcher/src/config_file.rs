@@ -0,0 +1,96 @@
+use anyhow::Result;
+use signal_hook::{
+    consts::{SIGHUP, SIGINT, SIGTERM},
+    iterator::Signals,
+};
+use std::{
+    fs, io, panic,
+    path::{Path, PathBuf},
+    process,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use crate::osc;
+
+// the config is often a symlink into a dotfiles repository, so the link target is written rather
+// than the link being replaced by a regular file
+fn resolve(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+// writes the contents to a temporary file next to the target, which then replaces the target, so
+// the file is never left half written
+pub fn write_atomic(path: &Path, contents: &str) -> io::Result<()> {
+    let path = resolve(path);
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp_path = path.with_file_name(format!(".{file_name}.{}.tmp", process::id()));
+
+    let result = fs::write(&temp_path, contents)
+        .and_then(|_| {
+            // keep the permissions of the original file
+            match fs::metadata(&path) {
+                Ok(metadata) => fs::set_permissions(&temp_path, metadata.permissions()),
+                Err(_) => Ok(()),
+            }
+        })
+        .and_then(|_| fs::rename(&temp_path, &path));
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+
+    result
+}
+
+// keeps a copy of the file as `<name>.bak` next to it
+pub fn backup(path: &Path) -> io::Result<PathBuf> {
+    let path = resolve(path);
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let backup_path = path.with_file_name(format!("{file_name}.bak"));
+
+    fs::copy(&path, &backup_path)?;
+
+    Ok(backup_path)
+}
+
+// writes back the original contents of the config if the process panics or is asked to stop by
+// SIGINT, SIGTERM or SIGHUP, the same way leaving with `Esc` does, and optionally resets the
+// colors changed with escape sequences, the original contents are shared so they can follow the
+// changes made to the config by other programs
+pub fn restore_on_exit(
+    path: PathBuf,
+    original: Arc<Mutex<String>>,
+    reset_colors: bool,
+) -> Result<()> {
+    let mut signals = Signals::new([SIGINT, SIGTERM, SIGHUP])?;
+
+    let restore = move || {
+        let original = original.lock().unwrap_or_else(|e| e.into_inner());
+        // avoid touching the file, and triggering alacritty's reload, when it is unchanged
+        if fs::read_to_string(&path).ok().as_ref() != Some(&*original) {
+            let _ = write_atomic(&path, &original);
+        }
+        if reset_colors {
+            let _ = osc::write(&osc::reset_sequence());
+        }
+    };
+
+    let signal_restore = restore.clone();
+    thread::spawn(move || {
+        if let Some(signal) = signals.forever().next() {
+            signal_restore();
+            ratatui::restore();
+            process::exit(128 + signal);
+        }
+    });
+
+    // chain with the hook installed by ratatui, which restores the terminal
+    let previous_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        restore();
+        previous_hook(info);
+    }));
+
+    Ok(())
+}
@@ -0,0 +1,326 @@
+use alacritty_theme_switcher::{
+    formats,
+    palette::{Palette, Rgb},
+    theme::Theme,
+};
+use anyhow::{anyhow, Result};
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    layout::Rect,
+    style::{Color, Style, Stylize},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, List, ListState, Paragraph, StatefulWidget, Widget},
+};
+use std::{fs, path::PathBuf};
+use toml_edit::{value, DocumentMut, Item, Table};
+
+// how much a key changes a red, green or blue channel
+const RGB_STEP: i16 = 4;
+
+// how much a key changes the hue, in degrees, and the saturation or lightness
+const HUE_STEP: f64 = 5.0;
+const PERCENT_STEP: f64 = 0.02;
+
+// text typed at the bottom of the editor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Prompt {
+    Hex(String),  // New value of the selected color.
+    Name(String), // Name of the theme to save.
+}
+
+// what the app should do after a key was handled by the editor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Continue,
+    Changed,      // A color changed, the preview must follow.
+    Cancel,       // Leave the editor without saving.
+    Save(String), // Save the edited palette as a new theme with this name.
+}
+
+// edits a copy of the palette of a theme, which is saved to a new file so the original is untouched
+#[derive(Debug)]
+pub struct Editor {
+    pub theme: Theme,           // The edited theme.
+    pub palette: Palette,       // The edited colors.
+    pub state: ListState,       // The selected slot, in the order of `Palette::slots`.
+    pub prompt: Option<Prompt>, // The text being typed, if any.
+    pub error: Option<String>,  // The last invalid input.
+}
+
+impl Editor {
+    pub fn new(theme: Theme) -> Editor {
+        Editor {
+            palette: theme.palette.clone().unwrap_or_default(),
+            theme,
+            state: ListState::default().with_selected(Some(0)),
+            prompt: None,
+            error: None,
+        }
+    }
+
+    fn slot(&self) -> (&'static str, &'static str) {
+        let slots = Palette::slots();
+        slots[self
+            .state
+            .selected()
+            .unwrap_or_default()
+            .min(slots.len() - 1)]
+    }
+
+    fn original(&self, table: &str, key: &str) -> Option<Rgb> {
+        self.theme.palette.as_ref()?.get(table, key)
+    }
+
+    pub fn color(&self) -> Option<Rgb> {
+        let (table, key) = self.slot();
+        self.palette.get(table, key)
+    }
+
+    fn set_color(&mut self, color: Option<Rgb>) -> Outcome {
+        let (table, key) = self.slot();
+        self.palette.set(table, key, color);
+        Outcome::Changed
+    }
+
+    // missing colors are adjusted from black
+    fn adjust_rgb(&mut self, channel: usize, delta: i16) -> Outcome {
+        let color = self.color().unwrap_or(Rgb::new(0, 0, 0));
+        let mut channels = [color.r, color.g, color.b];
+        channels[channel] = (channels[channel] as i16 + delta).clamp(0, 255) as u8;
+
+        self.set_color(Some(Rgb::new(channels[0], channels[1], channels[2])))
+    }
+
+    fn adjust_hsl(&mut self, hue: f64, saturation: f64, lightness: f64) -> Outcome {
+        let (h, s, l) = self.color().unwrap_or(Rgb::new(0, 0, 0)).to_hsl();
+
+        self.set_color(Some(Rgb::from_hsl(h + hue, s + saturation, l + lightness)))
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Outcome {
+        self.error = None;
+
+        if let Some(prompt) = &mut self.prompt {
+            let text = match prompt {
+                Prompt::Hex(text) | Prompt::Name(text) => text,
+            };
+
+            match key.code {
+                KeyCode::Char(c) => text.push(c),
+                KeyCode::Backspace => {
+                    text.pop();
+                }
+                KeyCode::Esc => self.prompt = None,
+                KeyCode::Enter => return self.submit(),
+                _ => {}
+            }
+
+            return Outcome::Continue;
+        }
+
+        let slots = Palette::slots().len();
+        let selected = self.state.selected().unwrap_or_default();
+
+        match key.code {
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                let name = format!("{} custom", self.theme.name);
+                self.prompt = Some(Prompt::Name(name));
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.state.select(Some(selected.saturating_sub(1))),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.state.select(Some((selected + 1).min(slots - 1)))
+            }
+            KeyCode::Char('r') => return self.adjust_rgb(0, -RGB_STEP),
+            KeyCode::Char('R') => return self.adjust_rgb(0, RGB_STEP),
+            KeyCode::Char('g') => return self.adjust_rgb(1, -RGB_STEP),
+            KeyCode::Char('G') => return self.adjust_rgb(1, RGB_STEP),
+            KeyCode::Char('b') => return self.adjust_rgb(2, -RGB_STEP),
+            KeyCode::Char('B') => return self.adjust_rgb(2, RGB_STEP),
+            KeyCode::Char('h') => return self.adjust_hsl(-HUE_STEP, 0.0, 0.0),
+            KeyCode::Char('H') => return self.adjust_hsl(HUE_STEP, 0.0, 0.0),
+            KeyCode::Char('s') => return self.adjust_hsl(0.0, -PERCENT_STEP, 0.0),
+            KeyCode::Char('S') => return self.adjust_hsl(0.0, PERCENT_STEP, 0.0),
+            KeyCode::Char('l') => return self.adjust_hsl(0.0, 0.0, -PERCENT_STEP),
+            KeyCode::Char('L') => return self.adjust_hsl(0.0, 0.0, PERCENT_STEP),
+            KeyCode::Char('u') => {
+                let (table, key) = self.slot();
+                return self.set_color(self.original(table, key));
+            }
+            KeyCode::Char('x') | KeyCode::Delete => return self.set_color(None),
+            KeyCode::Char('#') | KeyCode::Enter => {
+                let hex = self.color().map(|c| c.to_string()).unwrap_or_default();
+                self.prompt = Some(Prompt::Hex(hex));
+            }
+            KeyCode::Esc => return Outcome::Cancel,
+            _ => {}
+        }
+
+        Outcome::Continue
+    }
+
+    fn submit(&mut self) -> Outcome {
+        match self.prompt.take() {
+            Some(Prompt::Hex(text)) => {
+                // the `#` is optional
+                let hex = format!("#{}", text.trim().trim_start_matches('#'));
+                match Rgb::parse(&hex) {
+                    Some(color) => self.set_color(Some(color)),
+                    None => {
+                        self.error = Some(format!("Invalid color `{text}`, expected `#rrggbb`"));
+                        self.prompt = Some(Prompt::Hex(text));
+                        Outcome::Continue
+                    }
+                }
+            }
+            Some(Prompt::Name(name)) if !name.trim().is_empty() => {
+                Outcome::Save(name.trim().to_string())
+            }
+            Some(prompt) => {
+                self.prompt = Some(prompt);
+                Outcome::Continue
+            }
+            None => Outcome::Continue,
+        }
+    }
+
+    // one line per color slot, the changed ones are marked with `*`
+    pub fn items(&self) -> Vec<Line<'static>> {
+        Palette::slots()
+            .into_iter()
+            .map(|(table, key)| {
+                let color = self.palette.get(table, key);
+                let (swatch, hex): (Span, Span) = match color {
+                    Some(color) => ("  ".bg(color), format!(" {color}").into()),
+                    None => ("  ".into(), " -------".into()),
+                };
+                let changed = if color != self.original(table, key) {
+                    " *".yellow().bold()
+                } else {
+                    "".into()
+                };
+
+                Line::from(vec![
+                    format!(" {:<22}", format!("{table}.{key}")).into(),
+                    swatch,
+                    hex,
+                    changed,
+                ])
+            })
+            .collect()
+    }
+
+    // draws the prompt, or the values of the selected color, and the list of colors
+    pub fn render(&mut self, input_area: Rect, list_area: Rect, buf: &mut Buffer) {
+        let (title, text) = match &self.prompt {
+            Some(Prompt::Hex(text)) => ("Color".to_string(), text.clone()),
+            Some(Prompt::Name(text)) => ("Save as".to_string(), text.clone()),
+            None => {
+                let text = match self.color() {
+                    Some(color) => {
+                        let (h, s, l) = color.to_hsl();
+                        format!(
+                            "{color}  rgb({}, {}, {})  hsl({h:.0}, {:.0}%, {:.0}%)",
+                            color.r,
+                            color.g,
+                            color.b,
+                            s * 100.0,
+                            l * 100.0
+                        )
+                    }
+                    None => "not set".to_string(),
+                };
+                (format!("Editing {}", self.theme.name), text)
+            }
+        };
+
+        let mut input_block = Block::bordered()
+            .title(title)
+            .border_set(border::PLAIN)
+            .border_style(Style::default().fg(Color::Yellow));
+        if let Some(error) = &self.error {
+            input_block = input_block.title_bottom(Line::from(error.as_str()).red());
+        }
+
+        Paragraph::new(text)
+            .block(input_block)
+            .render(input_area, buf);
+
+        let msg = vec![
+            "rgb hsl".bold(),
+            " lower, ".into(),
+            "RGB HSL".bold(),
+            " raise, ".into(),
+            "#".bold(),
+            " hex, ".into(),
+            "u".bold(),
+            " undo, ".into(),
+            "ctrl-s".bold(),
+            " save, ".into(),
+            "esc".bold(),
+            " cancel".into(),
+        ];
+
+        let list = List::new(self.items())
+            .block(
+                Block::bordered()
+                    .title("Colors")
+                    .title_bottom(msg)
+                    .border_set(border::PLAIN),
+            )
+            .highlight_style(Style::new().reversed());
+
+        StatefulWidget::render(list, list_area, buf, &mut self.state);
+    }
+
+    // writes the edited palette next to the original theme, keeping the rest of the original file
+    pub fn save(&self, name: &str) -> Result<PathBuf> {
+        let path = self
+            .theme
+            .path
+            .with_file_name(format!("{}.toml", formats::file_name(name)));
+        if path.exists() {
+            return Err(anyhow!("{} already exists", path.display()));
+        }
+
+        let contents = fs::read_to_string(&self.theme.path).unwrap_or_default();
+        let mut doc: DocumentMut = contents
+            .parse()
+            .or_else(|_| self.palette.to_toml().parse())?;
+
+        for (table, key) in Palette::slots() {
+            let color = self.palette.get(table, key);
+            if color == self.original(table, key) {
+                continue;
+            }
+
+            let mut colors_table = Table::new();
+            colors_table.set_implicit(true);
+            let section = doc
+                .entry("colors")
+                .or_insert(Item::Table(colors_table))
+                .as_table_like_mut()
+                .and_then(|colors| {
+                    colors
+                        .entry(table)
+                        .or_insert(Item::Table(Table::new()))
+                        .as_table_like_mut()
+                })
+                .ok_or_else(|| anyhow!("colors.{table} is not a table"))?;
+
+            match color {
+                Some(color) => {
+                    section.insert(key, value(color.to_string()));
+                }
+                None => {
+                    section.remove(key);
+                }
+            }
+        }
+
+        fs::write(&path, doc.to_string())?;
+
+        Ok(path)
+    }
+}
@@ -0,0 +1,85 @@
+use anyhow::{anyhow, Result};
+use serde_yaml::{Mapping, Value};
+
+use super::{parse_hex, Imported};
+use crate::palette::Palette;
+
+// how the base16 colors are laid out on a terminal, following base16-shell
+// https://github.com/chriskempson/base16-shell
+const SLOTS: [(&str, &[(&str, &str)]); 11] = [
+    (
+        "base00",
+        &[
+            ("primary", "background"),
+            ("normal", "black"),
+            ("cursor", "text"),
+        ],
+    ),
+    ("base02", &[("selection", "background")]),
+    ("base03", &[("bright", "black")]),
+    (
+        "base05",
+        &[
+            ("primary", "foreground"),
+            ("normal", "white"),
+            ("cursor", "cursor"),
+            ("selection", "text"),
+        ],
+    ),
+    ("base07", &[("bright", "white")]),
+    ("base08", &[("normal", "red"), ("bright", "red")]),
+    ("base0A", &[("normal", "yellow"), ("bright", "yellow")]),
+    ("base0B", &[("normal", "green"), ("bright", "green")]),
+    ("base0C", &[("normal", "cyan"), ("bright", "cyan")]),
+    ("base0D", &[("normal", "blue"), ("bright", "blue")]),
+    ("base0E", &[("normal", "magenta"), ("bright", "magenta")]),
+];
+
+// reads a base16 scheme, either with the `baseXX` keys at the top level or under `palette`
+pub fn import(contents: &str) -> Result<Imported> {
+    let value: Value = serde_yaml::from_str(contents)?;
+    let root = value
+        .as_mapping()
+        .ok_or_else(|| anyhow!("Expected a mapping of colors"))?;
+
+    let colors: &Mapping = root
+        .get("palette")
+        .and_then(Value::as_mapping)
+        .unwrap_or(root);
+
+    let mut palette = Palette::default();
+    let mut unmapped = vec![];
+
+    for (key, value) in colors {
+        let (Some(key), Some(color)) = (key.as_str(), value.as_str().and_then(parse_hex)) else {
+            continue;
+        };
+
+        // the keys are case insensitive, `base0a` is as common as `base0A`, and base01, base04,
+        // base06, base09 and base0F are only used by the 256 color palette
+        let slots = SLOTS
+            .iter()
+            .find(|(base, _)| base.eq_ignore_ascii_case(key))
+            .map(|(_, slots)| *slots)
+            .unwrap_or_default();
+
+        if slots.is_empty() {
+            unmapped.push(key.to_string());
+        }
+
+        for (table, name) in slots {
+            palette.set(table, name, Some(color));
+        }
+    }
+
+    let name = ["name", "scheme"]
+        .iter()
+        .find_map(|key| root.get(*key).and_then(Value::as_str))
+        .map(String::from);
+
+    Ok(Imported {
+        name,
+        palette,
+        unmapped,
+    })
+}
@@ -0,0 +1,42 @@
+use super::ansi_slot;
+use crate::palette::{Palette, Rgb};
+
+// foot writes colors as `rrggbb`, without the leading `#`
+fn hex(color: Rgb) -> String {
+    color.to_string().trim_start_matches('#').to_string()
+}
+
+// writes the `[colors]` section of `foot.ini`
+pub fn export(palette: &Palette) -> String {
+    let mut lines = vec!["[colors]".to_string()];
+
+    let mut push = |key: &str, color: Option<Rgb>| {
+        if let Some(color) = color {
+            lines.push(format!("{key}={}", hex(color)));
+        }
+    };
+
+    push("foreground", palette.foreground);
+    push("background", palette.background);
+
+    for i in 0..16 {
+        let key = match i {
+            0..8 => format!("regular{i}"),
+            _ => format!("bright{}", i - 8),
+        };
+        push(
+            &key,
+            ansi_slot(i).and_then(|(table, name)| palette.get(table, name)),
+        );
+    }
+
+    push("selection-foreground", palette.selection_text);
+    push("selection-background", palette.selection_background);
+
+    // the cursor option holds both the text and the cursor colors
+    if let (Some(text), Some(cursor)) = (palette.cursor_text, palette.cursor) {
+        lines.push(format!("cursor={} {}", hex(text), hex(cursor)));
+    }
+
+    lines.join("\n") + "\n"
+}
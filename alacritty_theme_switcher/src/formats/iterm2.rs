@@ -0,0 +1,105 @@
+use anyhow::{anyhow, Result};
+use plist::{Dictionary, Value};
+use std::io::Cursor;
+
+use super::{ansi_slot, Imported};
+use crate::palette::{Palette, Rgb};
+
+// iterm2 color keys that have an alacritty equivalent, besides the ansi colors
+const KEYS: [&str; 6] = [
+    "Background Color",
+    "Foreground Color",
+    "Cursor Color",
+    "Cursor Text Color",
+    "Selection Color",
+    "Selected Text Color",
+];
+
+// returns the alacritty slot of an iterm2 color key
+fn slot(key: &str) -> Option<(&'static str, &'static str)> {
+    match key {
+        "Background Color" => Some(("primary", "background")),
+        "Foreground Color" => Some(("primary", "foreground")),
+        "Cursor Color" => Some(("cursor", "cursor")),
+        "Cursor Text Color" => Some(("cursor", "text")),
+        "Selection Color" => Some(("selection", "background")),
+        "Selected Text Color" => Some(("selection", "text")),
+        _ => {
+            // `Ansi 0 Color` to `Ansi 15 Color`
+            let index = key.strip_prefix("Ansi ")?.strip_suffix(" Color")?;
+            ansi_slot(index.parse().ok()?)
+        }
+    }
+}
+
+// colors are dictionaries of components between 0 and 1, the color space is ignored
+fn color(dict: &Dictionary) -> Option<Rgb> {
+    let component = |name: &str| {
+        let value = dict.get(name)?.as_real()?;
+        Some((value.clamp(0.0, 1.0) * 255.0).round() as u8)
+    };
+
+    Some(Rgb::new(
+        component("Red Component")?,
+        component("Green Component")?,
+        component("Blue Component")?,
+    ))
+}
+
+// reads an `.itermcolors` file, which is a plist in either the xml or the binary format
+pub fn import(bytes: &[u8]) -> Result<Imported> {
+    let value = Value::from_reader(Cursor::new(bytes))?;
+    let root = value
+        .as_dictionary()
+        .ok_or_else(|| anyhow!("Expected a dictionary of colors"))?;
+
+    let mut palette = Palette::default();
+    let mut unmapped = vec![];
+
+    for (key, value) in root {
+        let Some(color) = value.as_dictionary().and_then(color) else {
+            continue;
+        };
+
+        match slot(key) {
+            Some((table, name)) => palette.set(table, name, Some(color)),
+            None => unmapped.push(key.clone()),
+        }
+    }
+
+    Ok(Imported {
+        name: None,
+        palette,
+        unmapped,
+    })
+}
+
+pub fn export(palette: &Palette) -> Result<String> {
+    let keys = KEYS
+        .into_iter()
+        .map(String::from)
+        .chain((0..16).map(|i| format!("Ansi {i} Color")));
+
+    let mut root = Dictionary::new();
+    for key in keys {
+        let Some(color) = slot(&key).and_then(|(table, name)| palette.get(table, name)) else {
+            continue;
+        };
+
+        let component = |c: u8| Value::Real(c as f64 / 255.0);
+
+        let mut dict = Dictionary::new();
+        dict.insert("Alpha Component".to_string(), Value::Real(1.0));
+        dict.insert("Blue Component".to_string(), component(color.b));
+        dict.insert("Color Space".to_string(), Value::String("sRGB".to_string()));
+        dict.insert("Green Component".to_string(), component(color.g));
+        dict.insert("Red Component".to_string(), component(color.r));
+
+        root.insert(key, Value::Dictionary(dict));
+    }
+
+    let mut bytes = vec![];
+    Value::Dictionary(root).to_writer_xml(&mut bytes)?;
+
+    Ok(String::from_utf8(bytes)?)
+}
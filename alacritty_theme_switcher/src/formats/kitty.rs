@@ -0,0 +1,82 @@
+use super::{ansi_slot, parse_hex, Imported};
+use crate::palette::Palette;
+
+// kitty color options that have an alacritty equivalent, besides the ansi colors
+const OPTIONS: [&str; 6] = [
+    "background",
+    "foreground",
+    "cursor",
+    "cursor_text_color",
+    "selection_background",
+    "selection_foreground",
+];
+
+// returns the alacritty slot of a kitty color option
+fn slot(key: &str) -> Option<(&'static str, &'static str)> {
+    match key {
+        "background" => Some(("primary", "background")),
+        "foreground" => Some(("primary", "foreground")),
+        "cursor" => Some(("cursor", "cursor")),
+        "cursor_text_color" => Some(("cursor", "text")),
+        "selection_background" => Some(("selection", "background")),
+        "selection_foreground" => Some(("selection", "text")),
+        _ => ansi_slot(key.strip_prefix("color")?.parse().ok()?),
+    }
+}
+
+// reads a kitty `.conf` theme, made of `option value` lines
+pub fn import(contents: &str) -> Imported {
+    let mut name = None;
+    let mut palette = Palette::default();
+    let mut unmapped = vec![];
+
+    for line in contents.lines().map(str::trim) {
+        // themes from kitty-themes carry their name in a `## name: ...` comment
+        if let Some(value) = line.strip_prefix("## name:") {
+            name = Some(value.trim().to_string());
+            continue;
+        }
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once(char::is_whitespace) else {
+            continue;
+        };
+
+        // options such as `cursor_text_color background` don't hold a color
+        let Some(color) = parse_hex(value) else {
+            continue;
+        };
+
+        match slot(key) {
+            Some((table, key)) => palette.set(table, key, Some(color)),
+            None => unmapped.push(key.to_string()),
+        }
+    }
+
+    Imported {
+        name,
+        palette,
+        unmapped,
+    }
+}
+
+pub fn export(name: &str, palette: &Palette) -> String {
+    let mut lines = vec![format!("## name: {name}"), String::new()];
+
+    let options = OPTIONS
+        .into_iter()
+        .map(String::from)
+        .chain((0..16).map(|i| format!("color{i}")));
+
+    for option in options {
+        let color = slot(&option).and_then(|(table, key)| palette.get(table, key));
+        if let Some(color) = color {
+            lines.push(format!("{option} {color}"));
+        }
+    }
+
+    lines.join("\n") + "\n"
+}
@@ -0,0 +1,229 @@
+mod base16;
+mod foot;
+mod iterm2;
+mod kitty;
+mod wezterm;
+mod windows_terminal;
+mod xresources;
+
+use anyhow::{anyhow, Result};
+use clap::ValueEnum;
+use std::{fs, path::Path};
+
+use crate::palette::{Palette, Rgb, ANSI_NAMES};
+
+// theme formats of other terminals
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Iterm2,
+    Kitty,
+    WindowsTerminal,
+    Xresources,
+    Base16,
+}
+
+impl Format {
+    // guesses the format of a file from its name
+    pub fn detect(path: &Path) -> Option<Format> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        let extension = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        match extension.as_str() {
+            "itermcolors" => Some(Format::Iterm2),
+            "conf" => Some(Format::Kitty),
+            "json" => Some(Format::WindowsTerminal),
+            "yaml" | "yml" => Some(Format::Base16),
+            "xresources" | "xrdb" => Some(Format::Xresources),
+            _ if name.contains("xresources") => Some(Format::Xresources),
+            _ => None,
+        }
+    }
+}
+
+// formats alacritty themes can be exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Kitty,
+    Foot,
+    Wezterm,
+    Xresources,
+    Iterm2,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Kitty => "conf",
+            ExportFormat::Foot => "ini",
+            ExportFormat::Wezterm => "toml",
+            ExportFormat::Xresources => "Xresources",
+            ExportFormat::Iterm2 => "itermcolors",
+        }
+    }
+}
+
+// a palette read from the theme file of another terminal
+#[derive(Debug)]
+pub struct Imported {
+    pub name: Option<String>,  // Name of the scheme, when the file provides one.
+    pub palette: Palette,      // Colors mapped to the alacritty slots.
+    pub unmapped: Vec<String>, // Colors of the file that have no alacritty equivalent.
+}
+
+// reads the schemes defined in a theme file, most formats define a single one
+pub fn import(path: &Path, format: Format) -> Result<Vec<Imported>> {
+    let bytes = fs::read(path)?;
+
+    // every format except the plist can only be text
+    let text = || std::str::from_utf8(&bytes).map_err(|_| anyhow!("File is not valid UTF-8"));
+
+    let schemes = match format {
+        Format::Iterm2 => vec![iterm2::import(&bytes)?],
+        Format::Kitty => vec![kitty::import(text()?)],
+        Format::WindowsTerminal => windows_terminal::import(text()?)?,
+        Format::Xresources => vec![xresources::import(text()?)],
+        Format::Base16 => vec![base16::import(text()?)?],
+    };
+
+    if schemes.is_empty() {
+        return Err(anyhow!("No color scheme found in {}", path.display()));
+    }
+
+    Ok(schemes)
+}
+
+// writes a palette in the format of another terminal
+pub fn export(name: &str, palette: &Palette, format: ExportFormat) -> Result<String> {
+    let contents = match format {
+        ExportFormat::Kitty => kitty::export(name, palette),
+        ExportFormat::Foot => foot::export(palette),
+        ExportFormat::Wezterm => wezterm::export(name, palette),
+        ExportFormat::Xresources => xresources::export(palette),
+        ExportFormat::Iterm2 => iterm2::export(palette)?,
+    };
+
+    Ok(contents)
+}
+
+// returns the alacritty slot of one of the 16 ansi colors
+fn ansi_slot(index: usize) -> Option<(&'static str, &'static str)> {
+    match index {
+        0..8 => Some(("normal", ANSI_NAMES[index])),
+        8..16 => Some(("bright", ANSI_NAMES[index - 8])),
+        _ => None,
+    }
+}
+
+// parses colors written as `#rrggbb`, `rrggbb`, `0xrrggbb` or `#rgb`
+fn parse_hex(value: &str) -> Option<Rgb> {
+    let value = value.trim();
+    let hex = value.strip_prefix('#').unwrap_or(value);
+
+    if hex.len() == 3 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        let long: String = hex.chars().flat_map(|c| [c, c]).collect();
+        return Rgb::parse(&format!("#{long}"));
+    }
+
+    Rgb::parse(&format!("#{hex}")).or_else(|| Rgb::parse(value))
+}
+
+// turns a scheme name into a theme file name, following the style of the alacritty-theme repository
+pub fn file_name(name: &str) -> String {
+    let mut file_name = String::new();
+
+    for c in name.trim().to_lowercase().chars() {
+        if c.is_ascii_alphanumeric() || c == '-' {
+            file_name.push(c);
+        } else if !file_name.ends_with('_') {
+            file_name.push('_');
+        }
+    }
+
+    file_name.trim_matches('_').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a palette with a different color in every slot
+    fn full_palette() -> Palette {
+        let mut palette = Palette::default();
+        for (i, (table, key)) in Palette::slots().into_iter().enumerate() {
+            let i = i as u8;
+            palette.set(table, key, Some(Rgb::new(i * 10, 255 - i * 10, i * 7)));
+        }
+
+        palette
+    }
+
+    fn round_trip(format: ExportFormat, import: Format) -> (Palette, Imported) {
+        let palette = full_palette();
+        let exported = export("Round Trip", &palette, format).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir
+            .path()
+            .join(format!("round_trip.{}", format.extension()));
+        fs::write(&path, exported).unwrap();
+        let mut imported = super::import(&path, import).unwrap();
+
+        (palette, imported.remove(0))
+    }
+
+    #[test]
+    fn kitty_round_trip() {
+        let (palette, imported) = round_trip(ExportFormat::Kitty, Format::Kitty);
+
+        assert_eq!(imported.name.as_deref(), Some("Round Trip"));
+        assert_eq!(imported.palette, palette);
+        assert!(imported.unmapped.is_empty());
+    }
+
+    #[test]
+    fn iterm2_round_trip() {
+        let (palette, imported) = round_trip(ExportFormat::Iterm2, Format::Iterm2);
+
+        assert_eq!(imported.palette, palette);
+        assert!(imported.unmapped.is_empty());
+    }
+
+    #[test]
+    fn xresources_round_trip() {
+        let (mut palette, imported) = round_trip(ExportFormat::Xresources, Format::Xresources);
+
+        // xresources has no cursor text or selection colors
+        palette.cursor_text = None;
+        palette.selection_text = None;
+        palette.selection_background = None;
+
+        assert_eq!(imported.palette, palette);
+        assert!(imported.unmapped.is_empty());
+    }
+
+    #[test]
+    fn detect_exported_formats() {
+        for (format, import) in [
+            (ExportFormat::Kitty, Format::Kitty),
+            (ExportFormat::Xresources, Format::Xresources),
+            (ExportFormat::Iterm2, Format::Iterm2),
+        ] {
+            let path = Path::new("theme").with_extension(format.extension());
+            assert_eq!(Format::detect(&path), Some(import));
+        }
+    }
+
+    #[test]
+    fn parse_hex_formats() {
+        let color = Some(Rgb::new(0xaa, 0xbb, 0xcc));
+
+        assert_eq!(parse_hex("#aabbcc"), color);
+        assert_eq!(parse_hex("aabbcc"), color);
+        assert_eq!(parse_hex("0xaabbcc"), color);
+        assert_eq!(parse_hex("#abc"), color);
+        assert_eq!(parse_hex("background"), None);
+    }
+}
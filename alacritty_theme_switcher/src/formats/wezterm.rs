@@ -0,0 +1,41 @@
+use toml_edit::{value, Array, DocumentMut, Item, Table};
+
+use crate::palette::{Palette, Rgb};
+
+// writes a wezterm color scheme, as found in its `colors` directory
+pub fn export(name: &str, palette: &Palette) -> String {
+    let mut colors = Table::new();
+
+    let mut insert = |key: &str, color: Option<Rgb>| {
+        if let Some(color) = color {
+            colors.insert(key, value(color.to_string()));
+        }
+    };
+
+    insert("foreground", palette.foreground);
+    insert("background", palette.background);
+    insert("cursor_bg", palette.cursor);
+    insert("cursor_border", palette.cursor);
+    insert("cursor_fg", palette.cursor_text);
+    insert("selection_bg", palette.selection_background);
+    insert("selection_fg", palette.selection_text);
+
+    // wezterm expects all 8 colors, so incomplete tables are left out
+    for (key, ansi) in [("ansi", palette.normal), ("brights", palette.bright)] {
+        let Some(ansi) = ansi.into_iter().collect::<Option<Vec<_>>>() else {
+            continue;
+        };
+
+        let array: Array = ansi.iter().map(|c| c.to_string()).collect();
+        colors.insert(key, value(array));
+    }
+
+    let mut metadata = Table::new();
+    metadata.insert("name", value(name));
+
+    let mut doc = DocumentMut::new();
+    doc.insert("colors", Item::Table(colors));
+    doc.insert("metadata", Item::Table(metadata));
+
+    doc.to_string()
+}
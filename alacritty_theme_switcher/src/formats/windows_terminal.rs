@@ -0,0 +1,69 @@
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Value};
+
+use super::{parse_hex, Imported};
+use crate::palette::Palette;
+
+// returns the alacritty slot of a windows terminal scheme key
+fn slot(key: &str) -> Option<(&'static str, &'static str)> {
+    let slot = match key {
+        "background" => ("primary", "background"),
+        "foreground" => ("primary", "foreground"),
+        "cursorColor" => ("cursor", "cursor"),
+        "selectionBackground" => ("selection", "background"),
+        "black" => ("normal", "black"),
+        "red" => ("normal", "red"),
+        "green" => ("normal", "green"),
+        "yellow" => ("normal", "yellow"),
+        "blue" => ("normal", "blue"),
+        "purple" => ("normal", "magenta"),
+        "cyan" => ("normal", "cyan"),
+        "white" => ("normal", "white"),
+        "brightBlack" => ("bright", "black"),
+        "brightRed" => ("bright", "red"),
+        "brightGreen" => ("bright", "green"),
+        "brightYellow" => ("bright", "yellow"),
+        "brightBlue" => ("bright", "blue"),
+        "brightPurple" => ("bright", "magenta"),
+        "brightCyan" => ("bright", "cyan"),
+        "brightWhite" => ("bright", "white"),
+        _ => return None,
+    };
+
+    Some(slot)
+}
+
+fn import_scheme(scheme: &Map<String, Value>) -> Imported {
+    let mut palette = Palette::default();
+    let mut unmapped = vec![];
+
+    for (key, value) in scheme {
+        let Some(color) = value.as_str().and_then(parse_hex) else {
+            continue;
+        };
+
+        match slot(key) {
+            Some((table, key)) => palette.set(table, key, Some(color)),
+            None => unmapped.push(key.clone()),
+        }
+    }
+
+    Imported {
+        name: scheme.get("name").and_then(Value::as_str).map(String::from),
+        palette,
+        unmapped,
+    }
+}
+
+// reads either a single scheme, an array of schemes, or a whole `settings.json` with a `schemes` array
+pub fn import(contents: &str) -> Result<Vec<Imported>> {
+    let value: Value = serde_json::from_str(contents)?;
+
+    let schemes = match value.get("schemes").unwrap_or(&value) {
+        Value::Array(schemes) => schemes.iter().filter_map(Value::as_object).collect(),
+        Value::Object(scheme) => vec![scheme],
+        _ => return Err(anyhow!("Expected a color scheme object")),
+    };
+
+    Ok(schemes.into_iter().map(import_scheme).collect())
+}
@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use super::{ansi_slot, parse_hex, Imported};
+use crate::palette::Palette;
+
+// xresources color resources that have an alacritty equivalent, besides the ansi colors
+const RESOURCES: [&str; 3] = ["background", "foreground", "cursorColor"];
+
+// returns the alacritty slot of an xresources color resource
+fn slot(key: &str) -> Option<(&'static str, &'static str)> {
+    match key {
+        "background" => Some(("primary", "background")),
+        "foreground" => Some(("primary", "foreground")),
+        "cursorColor" => Some(("cursor", "cursor")),
+        _ => ansi_slot(key.strip_prefix("color")?.parse().ok()?),
+    }
+}
+
+// reads `*.color0: #rrggbb` style resources, `#define` macros are expanded
+pub fn import(contents: &str) -> Imported {
+    let mut defines = HashMap::new();
+    let mut palette = Palette::default();
+    let mut unmapped = vec![];
+
+    for line in contents.lines().map(str::trim) {
+        if let Some(define) = line.strip_prefix("#define") {
+            if let Some((name, value)) = define.trim().split_once(char::is_whitespace) {
+                defines.insert(name.to_string(), value.trim().to_string());
+            }
+            continue;
+        }
+
+        // `!` starts a comment, other preprocessor directives are ignored
+        if line.is_empty() || line.starts_with('!') || line.starts_with('#') {
+            continue;
+        }
+
+        let Some((resource, value)) = line.split_once(':') else {
+            continue;
+        };
+
+        let value = value.trim();
+        let value = defines.get(value).map(String::as_str).unwrap_or(value);
+        let Some(color) = parse_hex(value) else {
+            continue;
+        };
+
+        // `*.color0`, `*color0` and `URxvt.color0` all define `color0`
+        let key = resource
+            .rsplit(['.', '*'])
+            .next()
+            .unwrap_or(resource)
+            .trim();
+
+        match slot(key) {
+            Some((table, key)) => palette.set(table, key, Some(color)),
+            None => unmapped.push(resource.trim().to_string()),
+        }
+    }
+
+    Imported {
+        name: None,
+        palette,
+        unmapped,
+    }
+}
+
+pub fn export(palette: &Palette) -> String {
+    let resources = RESOURCES
+        .into_iter()
+        .map(String::from)
+        .chain((0..16).map(|i| format!("color{i}")));
+
+    let mut lines = vec![];
+    for resource in resources {
+        let color = slot(&resource).and_then(|(table, key)| palette.get(table, key));
+        if let Some(color) = color {
+            lines.push(format!("*.{resource}: {color}"));
+        }
+    }
+
+    lines.join("\n") + "\n"
+}
@@ -0,0 +1,256 @@
+use anyhow::Result;
+use std::path::Path;
+
+use crate::palette::{Palette, Rgb, AA_CONTRAST, LIGHT_LUMINANCE};
+
+// number of dominant colors extracted from the image
+const DOMINANT_COLORS: usize = 16;
+
+// contrast of the foreground against the background, WCAG level AAA
+const FOREGROUND_CONTRAST: f64 = 7.0;
+
+// hues of the red, green, yellow, blue, magenta and cyan ansi colors
+const ANSI_HUES: [(usize, f64); 6] = [
+    (1, 0.0),
+    (2, 120.0),
+    (3, 60.0),
+    (4, 240.0),
+    (5, 300.0),
+    (6, 180.0),
+];
+
+// image colors further than this from the hue of an ansi color are not used for it
+const MAX_HUE_DISTANCE: f64 = 35.0;
+
+// builds a palette from the dominant colors of a png or jpeg image
+pub fn from_image(path: &Path) -> Result<Palette> {
+    // a thumbnail has the same dominant colors and is much faster to go through
+    let image = image::open(path)?.thumbnail(128, 128).to_rgb8();
+    let pixels = image.pixels().map(|p| p.0).collect();
+
+    Ok(from_colors(&median_cut(pixels, DOMINANT_COLORS)))
+}
+
+// splits the pixels into boxes of similar colors, returning the average color of each box with
+// its number of pixels, the most common first
+fn median_cut(pixels: Vec<[u8; 3]>, count: usize) -> Vec<(Rgb, usize)> {
+    // the channel with the widest range of a box, and the range
+    let widest = |pixels: &[[u8; 3]]| {
+        (0..3)
+            .map(|c| {
+                let values = pixels.iter().map(|p| p[c]);
+                let range = values.clone().max().unwrap_or(0) - values.min().unwrap_or(0);
+                (c, range)
+            })
+            .max_by_key(|(_, range)| *range)
+            .unwrap_or((0, 0))
+    };
+
+    let mut boxes = vec![pixels];
+    while boxes.len() < count {
+        // split the box with the widest range at the median of that channel
+        let Some((index, (channel, _))) = boxes
+            .iter()
+            .map(|b| widest(b))
+            .enumerate()
+            .filter(|(_, (_, range))| *range > 0)
+            .max_by_key(|(_, (_, range))| *range)
+        else {
+            break;
+        };
+
+        let mut pixels = boxes.swap_remove(index);
+        pixels.sort_unstable_by_key(|p| p[channel]);
+        let upper = pixels.split_off(pixels.len() / 2);
+        boxes.push(pixels);
+        boxes.push(upper);
+    }
+
+    let mut colors: Vec<_> = boxes
+        .into_iter()
+        .filter(|b| !b.is_empty())
+        .map(|b| {
+            let channel =
+                |c: usize| (b.iter().map(|p| p[c] as usize).sum::<usize>() / b.len()) as u8;
+            (Rgb::new(channel(0), channel(1), channel(2)), b.len())
+        })
+        .collect();
+    colors.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+    colors
+}
+
+// shortest distance between two hues, in degrees
+fn hue_distance(a: f64, b: f64) -> f64 {
+    let distance = (a - b).rem_euclid(360.0);
+    distance.min(360.0 - distance)
+}
+
+// makes a color lighter on dark backgrounds, or darker on light ones, until it reaches the contrast
+fn with_contrast(color: Rgb, background: Rgb, contrast: f64) -> Rgb {
+    let (hue, saturation, mut lightness) = color.to_hsl();
+    let step = if background.luminance() > LIGHT_LUMINANCE {
+        -0.01
+    } else {
+        0.01
+    };
+
+    let mut color = color;
+    while color.contrast(&background) < contrast && (0.0..=1.0).contains(&(lightness + step)) {
+        lightness += step;
+        color = Rgb::from_hsl(hue, saturation, lightness);
+    }
+
+    color
+}
+
+// maps the dominant colors to the slots of a theme, the theme is light when the most common color
+// is, and every text color passes WCAG AA against the background
+fn from_colors(colors: &[(Rgb, usize)]) -> Palette {
+    let dominant = colors.first().map_or(Rgb::new(0, 0, 0), |(c, _)| *c);
+    let light = dominant.luminance() > LIGHT_LUMINANCE;
+    let (hue, saturation, _) = dominant.to_hsl();
+
+    // lightness of a dark theme, mirrored for a light theme
+    let lightness = |l: f64| if light { 1.0 - l } else { l };
+    let tint = |s: f64, l: f64| Rgb::from_hsl(hue, saturation.min(s), lightness(l));
+
+    let background = tint(0.35, 0.09);
+    let foreground = with_contrast(tint(0.15, 0.88), background, FOREGROUND_CONTRAST);
+
+    // the saturated colors of the image, ansi colors are picked among them by hue
+    let accents: Vec<_> = colors
+        .iter()
+        .map(|(c, _)| c.to_hsl())
+        .filter(|(_, s, l)| *s >= 0.2 && (0.15..=0.85).contains(l))
+        .collect();
+    let average_saturation = if accents.is_empty() {
+        0.55
+    } else {
+        accents.iter().map(|(_, s, _)| s).sum::<f64>() / accents.len() as f64
+    };
+
+    let text = |color: Rgb| Some(with_contrast(color, background, AA_CONTRAST));
+
+    let mut palette = Palette {
+        background: Some(background),
+        foreground: Some(foreground),
+        cursor: Some(foreground),
+        cursor_text: Some(background),
+        ..Palette::default()
+    };
+
+    for (index, target) in ANSI_HUES {
+        // the closest image color, or the usual hue when the image has nothing close
+        let closest = accents
+            .iter()
+            .map(|(h, s, _)| (hue_distance(*h, target), *h, *s))
+            .filter(|(distance, _, _)| *distance <= MAX_HUE_DISTANCE)
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+        let (h, s) = match closest {
+            Some((_, h, s)) => (h, s.max(0.45)),
+            None => (target, average_saturation.max(0.45)),
+        };
+
+        let normal = Rgb::from_hsl(h, s, lightness(0.6));
+        let bright = Rgb::from_hsl(h, s, lightness(0.72));
+        palette.normal[index] = text(normal);
+        palette.bright[index] = text(bright);
+    }
+
+    // normal black on dark themes, and normal white on light ones, are shades of the background
+    // rather than text colors, the other grays must be readable
+    if light {
+        palette.normal[0] = text(tint(0.2, 0.8));
+        palette.bright[0] = text(tint(0.2, 0.55));
+        palette.normal[7] = Some(tint(0.1, 0.15));
+        palette.bright[7] = text(tint(0.05, 0.35));
+    } else {
+        palette.normal[0] = Some(tint(0.3, 0.2));
+        palette.bright[0] = text(tint(0.2, 0.45));
+        palette.normal[7] = text(tint(0.1, 0.78));
+        palette.bright[7] = text(tint(0.05, 0.95));
+    }
+
+    let selection = tint(0.3, 0.25);
+    palette.selection_background = Some(selection);
+    palette.selection_text = Some(with_contrast(foreground, selection, AA_CONTRAST));
+
+    palette
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb as Pixel, RgbImage};
+    use std::path::PathBuf;
+
+    // writes an image with a main color taking the top half, and stripes of accents below
+    fn image(dir: &Path, name: &str, main: [u8; 3], accents: &[[u8; 3]]) -> PathBuf {
+        let image = RgbImage::from_fn(64, 64, |x, y| {
+            if y < 32 {
+                Pixel(main)
+            } else {
+                Pixel(accents[x as usize * accents.len() / 64])
+            }
+        });
+        let path = dir.join(name);
+        image.save(&path).unwrap();
+
+        path
+    }
+
+    // checks the contrast promised for the text colors of a generated theme
+    fn assert_readable(palette: &Palette, light: bool) {
+        let background = palette.background.unwrap();
+        let contrast = |color: Option<Rgb>| color.unwrap().contrast(&background);
+
+        // normal black on dark themes, and normal white on light ones, shade the background
+        let shade = if light { 7 } else { 0 };
+
+        assert!(contrast(palette.foreground) >= FOREGROUND_CONTRAST);
+        for i in 0..8 {
+            if i != shade {
+                assert!(contrast(palette.normal[i]) >= AA_CONTRAST, "normal {i}");
+            }
+            assert!(contrast(palette.bright[i]) >= AA_CONTRAST, "bright {i}");
+        }
+
+        let selection = palette.selection_background.unwrap();
+        assert!(palette.selection_text.unwrap().contrast(&selection) >= AA_CONTRAST);
+    }
+
+    #[test]
+    fn generate_readable_themes() {
+        let dir = tempfile::tempdir().unwrap();
+        let accents = [
+            [200, 40, 40],
+            [40, 160, 60],
+            [220, 200, 40],
+            [50, 80, 210],
+            [180, 60, 190],
+            [40, 190, 200],
+        ];
+
+        let dark = from_image(&image(dir.path(), "night.png", [20, 24, 48], &accents)).unwrap();
+        assert!(dark.background.unwrap().luminance() <= LIGHT_LUMINANCE);
+        assert_readable(&dark, false);
+
+        let light = from_image(&image(dir.path(), "snow.png", [235, 235, 225], &accents)).unwrap();
+        assert!(light.background.unwrap().luminance() > LIGHT_LUMINANCE);
+        assert_readable(&light, true);
+
+        // without accents, the ansi colors take their usual hue
+        let gray = from_image(&image(
+            dir.path(),
+            "fog.png",
+            [128, 128, 128],
+            &[[90, 90, 90]],
+        ))
+        .unwrap();
+        assert_readable(
+            &gray,
+            gray.background.unwrap().luminance() > LIGHT_LUMINANCE,
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use anyhow::{anyhow, Result};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use toml_edit::{Array, DocumentMut, Item};
+
+use crate::config_file::write_atomic;
+
+// number of recently applied themes that are remembered, for the order by last use
+const RECENT_LIMIT: usize = 50;
+
+// number of recently applied themes pinned at the top of the list
+const PINNED_RECENT: usize = 5;
+
+// favourite and recently applied themes, kept in `$XDG_STATE_HOME/alacritty_theme_switcher/history.toml`
+#[derive(Debug, Default)]
+pub struct History {
+    pub favourites: Vec<PathBuf>, // Themes pinned with a key, in the order they were added.
+    pub recent: Vec<PathBuf>,     // Applied themes, most recent first.
+}
+
+fn xdg() -> Result<xdg::BaseDirectories> {
+    Ok(xdg::BaseDirectories::with_prefix(
+        "alacritty_theme_switcher",
+    )?)
+}
+
+impl History {
+    pub fn load() -> Result<History> {
+        match xdg()?.find_state_file("history.toml") {
+            Some(path) => History::read(&path),
+            None => Ok(History::default()),
+        }
+    }
+
+    fn read(path: &Path) -> Result<History> {
+        let contents = fs::read_to_string(path)?;
+        let doc: DocumentMut = contents
+            .parse()
+            .map_err(|e| anyhow!("Failed to parse {}: {e}", path.display()))?;
+
+        let paths = |key: &str| -> Vec<PathBuf> {
+            doc.get(key)
+                .and_then(Item::as_array)
+                .map(|paths| {
+                    paths
+                        .iter()
+                        .filter_map(|p| p.as_str())
+                        .map(PathBuf::from)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        Ok(History {
+            favourites: paths("favourites"),
+            recent: paths("recent"),
+        })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = xdg()?.place_state_file("history.toml")?;
+
+        let paths = |paths: &[PathBuf]| -> Array {
+            paths
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect()
+        };
+
+        let mut doc = DocumentMut::new();
+        doc["favourites"] = Item::Value(paths(&self.favourites).into());
+        doc["recent"] = Item::Value(paths(&self.recent).into());
+
+        write_atomic(&path, &doc.to_string())?;
+
+        Ok(())
+    }
+
+    pub fn is_favourite(&self, path: &Path) -> bool {
+        self.favourites.iter().any(|p| p == path)
+    }
+
+    pub fn toggle_favourite(&mut self, path: &Path) {
+        if self.is_favourite(path) {
+            self.favourites.retain(|p| p != path);
+        } else {
+            self.favourites.push(path.to_path_buf());
+        }
+    }
+
+    // the most recently applied themes, which are pinned
+    pub fn pinned_recent(&self) -> &[PathBuf] {
+        &self.recent[..self.recent.len().min(PINNED_RECENT)]
+    }
+
+    // moves the theme to the front of the recent themes
+    pub fn push_recent(&mut self, path: &Path) {
+        self.recent.retain(|p| p != path);
+        self.recent.insert(0, path.to_path_buf());
+        self.recent.truncate(RECENT_LIMIT);
+    }
+}
@@ -0,0 +1,316 @@
+use anyhow::{anyhow, Result};
+use clap::ValueEnum;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+
+// what a key does, named in kebab-case in the settings file, e.g. `select-next`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Action {
+    Exit,
+    Apply,
+    ToggleSort,
+    ToggleFocus,
+    ToggleFavourite,
+    Undo,
+    Edit,
+    CycleSample,
+    ToggleProfiles,
+    SelectPrevious,
+    SelectNext,
+    PageUp,
+    PageDown,
+    SelectFirst,
+    SelectLast,
+    CursorLeft,
+    CursorRight,
+    WordLeft,
+    WordRight,
+    CursorStart,
+    CursorEnd,
+    DeleteBackward,
+    DeleteForward,
+    DeleteWord,
+    DeleteToStart,
+    DeleteToEnd,
+    None, // Removes a default binding.
+}
+
+// the part of the screen receiving the keys
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    #[default]
+    Filter, // Unbound characters are typed in the filter.
+    List, // Bindings of `[keys.list]` apply first.
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Key {
+        // the case of a character already tells whether shift was held, and backtab is shift-tab
+        let modifiers = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+
+        Key { code, modifiers }
+    }
+
+    pub fn from_event(event: &KeyEvent) -> Key {
+        Key::new(event.code, event.modifiers)
+    }
+
+    // parses keys such as `j`, `ctrl-n`, `alt-<`, `pagedown` or `ctrl-home`
+    pub fn parse(key: &str) -> Result<Key> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = key;
+
+        // a lone `-` is a key, not a separator
+        while let Some((modifier, tail)) = rest.split_once('-').filter(|(_, t)| !t.is_empty()) {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(anyhow!("Unknown modifier `{modifier}` in `{key}`")),
+            };
+            rest = tail;
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_lowercase().as_str() {
+                "enter" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "space" => KeyCode::Char(' '),
+                name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n) => KeyCode::F(n),
+                    None => return Err(anyhow!("Unknown key `{key}`")),
+                },
+            },
+        };
+
+        // shift is written as the uppercase letter, the shifted symbols depend on the layout
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                if !c.is_ascii_alphabetic() {
+                    return Err(anyhow!(
+                        "`{key}` can't be bound, use the shifted character itself"
+                    ));
+                }
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            code => code,
+        };
+
+        Ok(Key::new(code, modifiers))
+    }
+}
+
+const FILTER_BINDINGS: [(&str, Action); 37] = [
+    ("esc", Action::Exit),
+    ("enter", Action::Apply),
+    ("tab", Action::ToggleSort),
+    ("ctrl-space", Action::ToggleFocus),
+    ("ctrl-s", Action::ToggleFavourite),
+    ("ctrl-z", Action::Undo),
+    ("f2", Action::Edit),
+    ("alt-e", Action::Edit),
+    ("f3", Action::CycleSample),
+    ("ctrl-o", Action::CycleSample),
+    ("f4", Action::ToggleProfiles),
+    ("ctrl-r", Action::ToggleProfiles),
+    ("up", Action::SelectPrevious),
+    ("ctrl-p", Action::SelectPrevious),
+    ("down", Action::SelectNext),
+    ("ctrl-n", Action::SelectNext),
+    ("pageup", Action::PageUp),
+    ("pagedown", Action::PageDown),
+    ("ctrl-home", Action::SelectFirst),
+    ("alt-<", Action::SelectFirst),
+    ("ctrl-end", Action::SelectLast),
+    ("alt->", Action::SelectLast),
+    ("left", Action::CursorLeft),
+    ("ctrl-b", Action::CursorLeft),
+    ("right", Action::CursorRight),
+    ("ctrl-f", Action::CursorRight),
+    ("alt-b", Action::WordLeft),
+    ("alt-f", Action::WordRight),
+    ("home", Action::CursorStart),
+    ("ctrl-a", Action::CursorStart),
+    ("end", Action::CursorEnd),
+    ("ctrl-e", Action::CursorEnd),
+    ("backspace", Action::DeleteBackward),
+    ("delete", Action::DeleteForward),
+    ("ctrl-w", Action::DeleteWord),
+    ("ctrl-u", Action::DeleteToStart),
+    ("ctrl-k", Action::DeleteToEnd),
+];
+
+const LIST_BINDINGS: [(&str, Action); 1] = [("esc", Action::ToggleFocus)];
+
+// the key bindings, `[keys]` of the settings file applies everywhere and `[keys.list]` only when
+// the list is focused
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    filter: HashMap<Key, Action>,
+    list: HashMap<Key, Action>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = |defaults: &[(&str, Action)]| {
+            defaults
+                .iter()
+                .map(|(key, action)| (Key::parse(key).unwrap(), *action))
+                .collect()
+        };
+
+        Keymap {
+            filter: bindings(&FILTER_BINDINGS),
+            list: bindings(&LIST_BINDINGS),
+        }
+    }
+}
+
+impl Keymap {
+    pub fn bind(&mut self, focus: Focus, key: Key, action: Action) {
+        let bindings = match focus {
+            Focus::Filter => &mut self.filter,
+            Focus::List => &mut self.list,
+        };
+
+        bindings.insert(key, action);
+    }
+
+    // returns the action of a key, `None` when the key isn't bound
+    pub fn action(&self, focus: Focus, key: Key) -> Option<Action> {
+        let action = match focus {
+            Focus::List => self.list.get(&key).or_else(|| self.filter.get(&key)),
+            Focus::Filter => self.filter.get(&key),
+        };
+
+        action.copied().filter(|a| *a != Action::None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_keys() {
+        assert_eq!(
+            Key::parse("ctrl-n").unwrap(),
+            Key::new(KeyCode::Char('n'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            Key::parse("alt-<").unwrap(),
+            Key::new(KeyCode::Char('<'), KeyModifiers::ALT)
+        );
+        assert_eq!(
+            Key::parse("-").unwrap(),
+            Key::new(KeyCode::Char('-'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            Key::parse("ctrl-PageDown").unwrap(),
+            Key::new(KeyCode::PageDown, KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            Key::parse("f12").unwrap(),
+            Key::new(KeyCode::F(12), KeyModifiers::NONE)
+        );
+        assert!(Key::parse("hyper-j").is_err());
+        assert!(Key::parse("pgdn").is_err());
+    }
+
+    #[test]
+    fn parse_shifted_keys() {
+        assert_eq!(
+            Key::parse("shift-j").unwrap(),
+            Key::new(KeyCode::Char('J'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            Key::parse("ctrl-shift-j").unwrap(),
+            Key::new(KeyCode::Char('J'), KeyModifiers::CONTROL)
+        );
+        assert!(Key::parse("shift-1").is_err());
+    }
+
+    #[test]
+    fn bound_actions() {
+        let mut keymap = Keymap::default();
+        keymap.bind(
+            Focus::Filter,
+            Key::parse("shift-j").unwrap(),
+            Action::SelectNext,
+        );
+        keymap.bind(
+            Focus::List,
+            Key::parse("k").unwrap(),
+            Action::SelectPrevious,
+        );
+        keymap.bind(Focus::Filter, Key::parse("ctrl-z").unwrap(), Action::None);
+        keymap.bind(
+            Focus::Filter,
+            Key::parse("backtab").unwrap(),
+            Action::ToggleFocus,
+        );
+
+        let key = |code, modifiers| Key::from_event(&KeyEvent::new(code, modifiers));
+
+        // terminals send the uppercase letter with shift
+        assert_eq!(
+            keymap.action(Focus::Filter, key(KeyCode::Char('J'), KeyModifiers::SHIFT)),
+            Some(Action::SelectNext)
+        );
+        assert_eq!(
+            keymap.action(Focus::Filter, key(KeyCode::Char('j'), KeyModifiers::NONE)),
+            None
+        );
+        assert_eq!(
+            keymap.action(Focus::Filter, key(KeyCode::BackTab, KeyModifiers::SHIFT)),
+            Some(Action::ToggleFocus)
+        );
+
+        // the list bindings only apply to the list, which falls back to the others
+        assert_eq!(
+            keymap.action(Focus::List, key(KeyCode::Char('k'), KeyModifiers::NONE)),
+            Some(Action::SelectPrevious)
+        );
+        assert_eq!(
+            keymap.action(Focus::Filter, key(KeyCode::Char('k'), KeyModifiers::NONE)),
+            None
+        );
+        assert_eq!(
+            keymap.action(Focus::List, key(KeyCode::Down, KeyModifiers::NONE)),
+            Some(Action::SelectNext)
+        );
+
+        // `none` removes a default binding
+        assert_eq!(
+            keymap.action(
+                Focus::Filter,
+                key(KeyCode::Char('z'), KeyModifiers::CONTROL)
+            ),
+            None
+        );
+    }
+}
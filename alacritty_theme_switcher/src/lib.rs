@@ -0,0 +1,20 @@
+// the parts of the theme switcher that don't depend on the terminal UI, so other tools can embed
+// them: locating and rewriting the alacritty config, finding, matching and linting themes, and
+// converting them from and to the formats of other terminals
+pub mod config;
+pub mod config_file;
+pub mod formats;
+pub mod generate;
+pub mod history;
+pub mod keys;
+pub mod lint;
+pub mod matcher;
+pub mod osc;
+pub mod palette;
+pub mod profile;
+pub mod schedule;
+pub mod settings;
+pub mod store;
+pub mod theme;
+pub mod watch;
+pub mod yaml;
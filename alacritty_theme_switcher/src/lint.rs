@@ -0,0 +1,203 @@
+use std::fmt;
+use toml_edit::{DocumentMut, Item, TableLike};
+
+use crate::palette::{Rgb, ANSI_NAMES};
+
+// a problem found in a theme file, which alacritty would log as an error
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub key: String,     // Dotted path of the offending key, empty for the whole file.
+    pub message: String, // What is wrong with it.
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.key.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.key, self.message)
+        }
+    }
+}
+
+// keys of `colors.primary`, which only take colors
+const PRIMARY_KEYS: [&str; 4] = [
+    "foreground",
+    "background",
+    "dim_foreground",
+    "bright_foreground",
+];
+
+// tables of `colors` whose colors can also be `CellForeground` or `CellBackground`
+fn cell_keys(table: &str) -> Option<&'static [&'static str]> {
+    match table {
+        "cursor" | "vi_mode_cursor" => Some(&["text", "cursor"]),
+        "selection" => Some(&["text", "background"]),
+        "line_indicator" | "footer_bar" => Some(&["foreground", "background"]),
+        _ => None,
+    }
+}
+
+// tables of `colors` made of `foreground` and `background` pairs
+fn nested_keys(table: &str) -> Option<&'static [&'static str]> {
+    match table {
+        "search" => Some(&["matches", "focused_match"]),
+        "hints" => Some(&["start", "end"]),
+        _ => None,
+    }
+}
+
+struct Linter {
+    problems: Vec<Problem>,
+}
+
+impl Linter {
+    fn report(&mut self, key: &str, message: impl Into<String>) {
+        self.problems.push(Problem {
+            key: key.to_string(),
+            message: message.into(),
+        });
+    }
+
+    fn table<'a>(&mut self, key: &str, item: &'a Item) -> Option<&'a dyn TableLike> {
+        let table = item.as_table_like();
+        if table.is_none() {
+            self.report(key, "expected a table");
+        }
+        table
+    }
+
+    fn color(&mut self, key: &str, item: &Item, cell: bool) {
+        let Some(value) = item.as_str() else {
+            self.report(key, "expected a color string");
+            return;
+        };
+
+        let is_cell = matches!(value, "CellForeground" | "CellBackground");
+        if Rgb::parse(value).is_none() && !(cell && is_cell) {
+            self.report(key, format!("invalid color `{value}`, expected `#rrggbb`"));
+        }
+    }
+
+    fn colors(&mut self, key: &str, item: &Item, keys: &[&str], cell: bool) {
+        let Some(table) = self.table(key, item) else {
+            return;
+        };
+
+        for (name, item) in table.iter() {
+            let key = format!("{key}.{name}");
+            if keys.contains(&name) {
+                self.color(&key, item, cell);
+            } else {
+                self.report(
+                    &key,
+                    format!("unknown key, expected one of {}", keys.join(", ")),
+                );
+            }
+        }
+    }
+
+    fn indexed_colors(&mut self, key: &str, item: &Item) {
+        let entries: Vec<_> = match item {
+            Item::ArrayOfTables(array) => array.iter().map(|t| t as &dyn TableLike).collect(),
+            Item::Value(value) => match value.as_array() {
+                Some(array) => array
+                    .iter()
+                    .filter_map(|v| v.as_inline_table().map(|t| t as &dyn TableLike))
+                    .collect(),
+                None => return self.report(key, "expected an array of tables"),
+            },
+            _ => return self.report(key, "expected an array of tables"),
+        };
+
+        for (i, entry) in entries.into_iter().enumerate() {
+            let key = format!("{key}[{i}]");
+
+            match entry.get("index").and_then(Item::as_integer) {
+                Some(16..=255) => {}
+                _ => self.report(
+                    &format!("{key}.index"),
+                    "expected an integer from 16 to 255",
+                ),
+            }
+
+            match entry.get("color") {
+                Some(color) => self.color(&format!("{key}.color"), color, false),
+                None => self.report(&format!("{key}.color"), "missing"),
+            }
+        }
+    }
+}
+
+// checks a theme file against the color options of alacritty
+// https://alacritty.org/config-alacritty.html#s12
+pub fn lint(contents: &str) -> Vec<Problem> {
+    let mut linter = Linter { problems: vec![] };
+
+    let doc: DocumentMut = match contents.parse() {
+        Ok(doc) => doc,
+        Err(e) => {
+            // the first line has the position, the last one the reason
+            let message = e.to_string();
+            let lines: Vec<_> = message.lines().filter(|l| !l.trim().is_empty()).collect();
+            let mut message = match (lines.first(), lines.last()) {
+                (Some(first), Some(last)) if first != last => format!("{first}: {last}"),
+                _ => message.trim().to_string(),
+            };
+
+            // themes from before alacritty 0.13 were written in yaml
+            if contents.lines().any(|l| l.trim_end() == "colors:") {
+                message.push_str(", this looks like a legacy YAML theme");
+            }
+
+            linter.report("", message);
+            return linter.problems;
+        }
+    };
+
+    let Some(colors) = doc.get("colors") else {
+        linter.report("colors", "missing, the theme defines no colors");
+        return linter.problems;
+    };
+    let Some(colors) = linter.table("colors", colors) else {
+        return linter.problems;
+    };
+
+    for (name, item) in colors.iter() {
+        let key = format!("colors.{name}");
+
+        match name {
+            "primary" => linter.colors(&key, item, &PRIMARY_KEYS, false),
+            "normal" | "bright" | "dim" => linter.colors(&key, item, &ANSI_NAMES, false),
+            "indexed_colors" => linter.indexed_colors(&key, item),
+            "transparent_background_colors" | "draw_bold_text_with_bright_colors" => {
+                if item.as_bool().is_none() {
+                    linter.report(&key, "expected true or false");
+                }
+            }
+            _ => {
+                if let Some(keys) = cell_keys(name) {
+                    linter.colors(&key, item, keys, true);
+                } else if let Some(keys) = nested_keys(name) {
+                    let Some(table) = linter.table(&key, item) else {
+                        continue;
+                    };
+
+                    for (nested, item) in table.iter() {
+                        let key = format!("{key}.{nested}");
+                        if keys.contains(&nested) {
+                            linter.colors(&key, item, &["foreground", "background"], true);
+                        } else {
+                            let expected = keys.join(", ");
+                            linter.report(&key, format!("unknown key, expected one of {expected}"));
+                        }
+                    }
+                } else {
+                    linter.report(&key, "unknown key");
+                }
+            }
+        }
+    }
+
+    linter.problems
+}
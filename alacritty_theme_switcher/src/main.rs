@@ -1,64 +1,361 @@
-use anyhow::{anyhow, Result};
-use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+mod commands;
+mod editor;
+mod sample;
+mod ui;
+
+use alacritty_theme_switcher::{
+    config::{find_config, Config},
+    config_file::restore_on_exit,
+    formats::{ExportFormat, Format},
+    history::History,
+    keys::{Action, Focus, Key, Keymap},
+    matcher::{self, Match, Matcher, SortMode},
+    osc::{self, PreviewMode},
+    palette::Palette,
+    profile::{load_profiles, Override, Profile},
+    schedule::Schedule,
+    settings::Settings,
+    store::{find_theme_dirs, ThemeStore},
+    theme::Theme,
+    watch::Watcher,
+};
+use anyhow::Result;
+use clap::{command, value_parser, Arg, ArgAction, Command};
 use ratatui::{
-    buffer::Buffer,
-    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
-    layout::{Constraint, Layout, Rect},
-    style::{Color, Style, Stylize},
-    symbols::border,
-    text::{Line, Text},
-    widgets::{Block, List, ListDirection, ListState, Paragraph, StatefulWidget, Widget},
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::Position,
+    widgets::ListState,
     DefaultTerminal, Frame,
 };
-use std::{env, fs, path::PathBuf};
-use toml::{Table, Value};
+use std::{
+    path::{Path, PathBuf},
+    process::ExitCode,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
-fn main() -> Result<()> {
-    let mut terminal = ratatui::init();
-    let app_result = ThemeChanger::default().run(&mut terminal);
-    ratatui::restore();
+use editor::{Editor, Outcome, Prompt};
+use sample::Sample;
+
+// how long to wait for a key before looking for changes to the themes and the config
+const REFRESH_INTERVAL: Duration = Duration::from_millis(250);
+
+fn main() -> Result<ExitCode> {
+    // parse the command line arguments
+    let matches =
+        command!()
+            .arg(
+                Arg::new("themes-dir")
+                    .long("themes-dir")
+                    .short('t')
+                    .global(true)
+                    .action(ArgAction::Append)
+                    .value_parser(value_parser!(PathBuf))
+                    .help("Directory to scan for themes, can be repeated"),
+            )
+            .arg(
+                Arg::new("config")
+                    .long("config")
+                    .short('c')
+                    .global(true)
+                    .value_parser(value_parser!(PathBuf))
+                    .help("Config file to change (default: $ALACRITTY_CONFIG, or the one alacritty reads)"),
+            )
+            .arg(
+                Arg::new("preview")
+                    .long("preview")
+                    .short('p')
+                    .value_parser(value_parser!(PreviewMode))
+                    .help("How the selected theme is previewed (default: config)"),
+            )
+            .subcommand(
+                Command::new("list").about("List the available themes").arg(
+                    Arg::new("filter").help("Only list the themes matching this fuzzy filter"),
+                ),
+            )
+            .subcommand(Command::new("current").about("Print the currently imported theme"))
+            .subcommand(
+                Command::new("lint").about("Check every theme against alacritty's color options"),
+            )
+            .subcommand(Command::new("profiles").about("List the profiles"))
+            .subcommand(
+                Command::new("profile")
+                    .about("Import the theme of the given profile and set its options")
+                    .arg(Arg::new("name").required(true).help("Name of the profile")),
+            )
+            .subcommand(Command::new("undo").about("Import the previously applied theme again"))
+            .subcommand(
+                Command::new("schedule")
+                    .about("Keep importing the themes of the schedule as the time of day changes"),
+            )
+            .subcommand(Command::new("apply-scheduled").about(
+                "Import the theme scheduled for the current time, for cron or systemd timers",
+            ))
+            .subcommand(
+                Command::new("set")
+                    .about("Import the given theme")
+                    .arg(Arg::new("name").required(true).help("Name of the theme")),
+            )
+            .subcommand(Command::new("random").about("Import a random theme").arg(
+                Arg::new("filter").help("Only pick among the themes matching this fuzzy filter"),
+            ))
+            .subcommand(
+                Command::new("import")
+                    .about("Convert a theme of another terminal into an alacritty theme")
+                    .arg(
+                        Arg::new("file")
+                            .required(true)
+                            .value_parser(value_parser!(PathBuf))
+                            .help("Theme file to convert"),
+                    )
+                    .arg(
+                        Arg::new("format")
+                            .long("format")
+                            .short('f')
+                            .value_parser(value_parser!(Format))
+                            .help("Format of the file (default: guessed from the file name)"),
+                    )
+                    .arg(
+                        Arg::new("name")
+                            .long("name")
+                            .short('n')
+                            .help("Name of the new theme (default: the scheme or file name)"),
+                    )
+                    .arg(
+                        Arg::new("force")
+                            .long("force")
+                            .num_args(0)
+                            .default_value("false")
+                            .value_parser(value_parser!(bool))
+                            .help("Overwrite existing themes"),
+                    ),
+            )
+            .subcommand(
+                Command::new("generate")
+                    .about("Generate a theme from the dominant colors of a PNG or JPEG image")
+                    .arg(
+                        Arg::new("from-image")
+                            .long("from-image")
+                            .required(true)
+                            .value_parser(value_parser!(PathBuf))
+                            .help("Image to take the colors from, such as a wallpaper"),
+                    )
+                    .arg(
+                        Arg::new("name")
+                            .long("name")
+                            .short('n')
+                            .help("Name of the new theme (default: the image file name)"),
+                    )
+                    .arg(
+                        Arg::new("force")
+                            .long("force")
+                            .num_args(0)
+                            .default_value("false")
+                            .value_parser(value_parser!(bool))
+                            .help("Overwrite an existing theme"),
+                    ),
+            )
+            .subcommand(
+                Command::new("migrate")
+                    .about("Convert a legacy alacritty.yml config and its themes to TOML")
+                    .arg(
+                        Arg::new("force")
+                            .long("force")
+                            .num_args(0)
+                            .default_value("false")
+                            .value_parser(value_parser!(bool))
+                            .help("Overwrite existing TOML files"),
+                    ),
+            )
+            .subcommand(
+                Command::new("export")
+                    .about("Convert an alacritty theme into a theme of another terminal")
+                    .arg(Arg::new("theme").required(true).help("Name of the theme"))
+                    .arg(
+                        Arg::new("to")
+                            .long("to")
+                            .required(true)
+                            .value_parser(value_parser!(ExportFormat))
+                            .help("Format to convert to"),
+                    )
+                    .arg(
+                        Arg::new("output")
+                            .long("output")
+                            .short('o')
+                            .value_parser(value_parser!(PathBuf))
+                            .help(
+                                "File to write (default: the theme name in the current directory)",
+                            ),
+                    ),
+            )
+            .get_matches();
+
+    let mut app = ThemeChanger::default();
+    if let Some(dirs) = matches.get_many::<PathBuf>("themes-dir") {
+        app.store.dirs = dirs.cloned().collect();
+    }
+    app.preview_mode = matches.get_one::<PreviewMode>("preview").copied();
+    app.load(matches.get_one::<PathBuf>("config").map(PathBuf::as_path))?;
 
-    app_result
+    // the interface shows the warnings of loading, the commands print them
+    if matches.subcommand().is_some() {
+        if let Some(notice) = app.notice.take() {
+            eprintln!("warning: {notice}");
+        }
+    }
+
+    match matches.subcommand() {
+        Some(("list", args)) => app.list(args.get_one::<String>("filter")),
+        Some(("current", _)) => app.current(),
+        Some(("lint", _)) => app.lint(),
+        Some(("profiles", _)) => app.profiles(),
+        Some(("profile", args)) => app.profile(args.get_one::<String>("name").unwrap()),
+        Some(("undo", _)) => app.undo(),
+        Some(("schedule", _)) => app.schedule(),
+        Some(("apply-scheduled", _)) => app.apply_scheduled(),
+        Some(("set", args)) => app.set(args.get_one::<String>("name").unwrap()),
+        Some(("random", args)) => app.random(args.get_one::<String>("filter")),
+        Some(("import", args)) => app.import(
+            args.get_one::<PathBuf>("file").unwrap(),
+            args.get_one::<Format>("format").copied(),
+            args.get_one::<String>("name"),
+            *args.get_one::<bool>("force").unwrap(),
+        ),
+        Some(("generate", args)) => app.generate(
+            args.get_one::<PathBuf>("from-image").unwrap(),
+            args.get_one::<String>("name"),
+            *args.get_one::<bool>("force").unwrap(),
+        ),
+        Some(("migrate", args)) => app.migrate(*args.get_one::<bool>("force").unwrap()),
+        Some(("export", args)) => app.export(
+            args.get_one::<String>("theme").unwrap(),
+            *args.get_one::<ExportFormat>("to").unwrap(),
+            args.get_one::<PathBuf>("output"),
+        ),
+        _ => {
+            let mut terminal = ratatui::init();
+            let app_result = app.run(&mut terminal);
+            ratatui::restore();
+
+            app_result.map(|_| ExitCode::SUCCESS)
+        }
+    }
 }
 
 #[derive(Default, Debug)]
 pub struct ThemeChanger {
-    config_path: PathBuf,   // Path to the config file.
-    config_table: Table,    // Table containing the config file contents.
-    themes: Vec<PathBuf>,   // List of themes found in the themes directory.
-    input: String,          // The value of the search input field.
-    character_index: usize, // The index of the cursor in the input field.
-    state: ListState,       // The state of the list widget.
-    exit: bool,             // Whether the app should exit.
+    config: Config,                              // The alacritty config file.
+    original: Arc<Mutex<String>>,                // The config to write back if stopped by a signal.
+    store: ThemeStore,                           // The theme directories and their themes.
+    profiles: Vec<Profile>,                      // The themes bundled with config options.
+    show_profiles: bool,                         // Whether the list shows the profiles.
+    input: String,                               // The value of the search input field.
+    character_index: usize,                      // The index of the cursor in the input field.
+    matcher: Matcher,                            // The fuzzy matcher, built once.
+    matches: Vec<Match>,                         // The themes matching the input, in list order.
+    state: ListState,                            // The state of the list widget.
+    page_size: usize,                            // The number of visible themes.
+    keymap: Keymap,                              // The key bindings.
+    history: History,                            // The favourite and recent themes.
+    schedule: Option<Schedule>,                  // The themes to apply at given times of the day.
+    editor: Option<Editor>,                      // The theme editor, while it is open.
+    focus: Focus,                                // Whether the filter or the list gets the keys.
+    sort_mode: SortMode,                         // The order of the list widget.
+    preview_mode: Option<PreviewMode>,           // How the selected theme is previewed.
+    preview: Option<(PathBuf, Result<Palette>)>, // The palette of the selected theme.
+    sample: Sample,                              // The sample drawn with the selected theme.
+    applied: Option<(PathBuf, Option<String>)>,  // The theme, and profile, last applied.
+    watcher: Option<Watcher>,                    // Watches the themes and the config for changes.
+    error: Option<String>,                       // The last error that occurred while previewing.
+    notice: Option<String>,                      // A warning shown until the next key press.
+    exit: bool,                                  // Whether the app should exit.
 }
 
 impl ThemeChanger {
+    // reads the config given with `--config`, or the one alacritty would read
+    pub fn load(&mut self, config: Option<&Path>) -> Result<()> {
+        self.config = Config::read(find_config(config)?)?;
+        let settings = Settings::load()?;
+        let theme_dirs = find_theme_dirs(&self.store.dirs, &settings, &self.config.path)?;
+        if self.preview_mode.is_none() {
+            self.preview_mode = settings.preview;
+        }
+        self.keymap = settings.keys;
+        self.schedule = settings.schedule;
+        self.store = ThemeStore::new(theme_dirs, self.config.format);
+        // the history is only written by this tool, a damaged one is started afresh
+        self.history = History::load().unwrap_or_else(|e| {
+            // the parse errors quote the file on the next lines, which don't fit in a notice
+            let error = e.to_string();
+            let error = error.lines().next().unwrap_or_default();
+            self.notice = Some(format!(
+                "Failed to read the history, it starts empty: {error}"
+            ));
+            History::default()
+        });
+        self.profiles = load_profiles()?;
+        self.update_matches();
+
+        Ok(())
+    }
+
     pub fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
-        self.config_path = self.find_config()?;
-        self.config_table = self.read_config()?;
-        self.themes = self.scan_themes()?;
+        self.original = Arc::new(Mutex::new(self.config.contents.clone()));
+        restore_on_exit(
+            self.config.path.clone(),
+            self.original.clone(),
+            self.preview_mode == Some(PreviewMode::Osc),
+        )?;
+
+        // the interface still works without live updates, e.g. when out of inotify watches
+        match Watcher::new(&self.store.dirs, &self.config.path) {
+            Ok(watcher) => self.watcher = Some(watcher),
+            Err(e) => self.notice = Some(format!("Failed to watch the themes: {e}")),
+        }
 
-        // select the first theme
-        self.state.select_first();
+        // select the theme imported by the config, or the first theme
+        self.select_current_theme();
         self.update_theme();
 
         while !self.exit {
+            self.update_preview();
             terminal.draw(|frame| self.draw(frame))?;
             self.update_theme();
             self.handle_events()?;
+            self.refresh();
         }
 
         Ok(())
     }
 
     fn draw(&mut self, frame: &mut Frame) {
-        frame.render_widget(self, frame.area());
+        let area = frame.area();
+
+        // show the cursor in the filter while it has the focus, or in the prompt of the editor
+        let cursor = match &self.editor {
+            Some(editor) => match &editor.prompt {
+                Some(Prompt::Hex(text) | Prompt::Name(text)) => Some(text.chars().count()),
+                None => None,
+            },
+            None if self.focus == Focus::Filter => Some(self.character_index),
+            None => None,
+        };
+        if let Some(cursor) = cursor {
+            // inside the border
+            frame.set_cursor_position(Position::new(area.x + cursor as u16 + 1, area.y + 1));
+        }
+
+        frame.render_widget(self, area);
     }
 
     fn handle_events(&mut self) -> Result<()> {
+        if !event::poll(REFRESH_INTERVAL)? {
+            return Ok(());
+        }
+
         match event::read()? {
             Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
-                self.handle_key_event(key_event)
+                self.handle_key_event(key_event)?
             }
             _ => {}
         };
@@ -66,24 +363,269 @@ impl ThemeChanger {
         Ok(())
     }
 
-    fn handle_key_event(&mut self, key: KeyEvent) {
-        match key.code {
-            KeyCode::Char(to_insert) => self.enter_char(to_insert),
-            KeyCode::Enter => self.exit(false),
-            KeyCode::Backspace => self.delete_char(),
-            KeyCode::Up => self.select_previous(),
-            KeyCode::Down => self.select_next(),
-            KeyCode::Esc => self.exit(true),
-            _ => {}
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<()> {
+        self.notice = None;
+
+        // the terminal is in raw mode, so ctrl-c doesn't send SIGINT, it can't be rebound
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return self.exit(true);
         }
+
+        if self.editor.is_some() {
+            self.handle_editor_key(key);
+            return Ok(());
+        }
+
+        let Some(action) = self.keymap.action(self.focus, Key::from_event(&key)) else {
+            // unbound characters are typed in the filter, which takes the focus back
+            if let KeyCode::Char(to_insert) = key.code {
+                if !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+                {
+                    self.focus = Focus::Filter;
+                    self.enter_char(to_insert);
+                }
+            }
+            return Ok(());
+        };
+
+        match action {
+            Action::Exit => self.exit(true)?,
+            Action::Apply => self.exit(false)?,
+            Action::ToggleSort => self.toggle_sort_mode(),
+            Action::ToggleFocus => self.toggle_focus(),
+            Action::ToggleFavourite => self.toggle_favourite(),
+            Action::Undo => self.select_previous_theme(),
+            Action::Edit => self.open_editor(),
+            Action::CycleSample => self.sample = self.sample.next(),
+            Action::ToggleProfiles => self.toggle_profiles(),
+            Action::SelectPrevious => self.select_previous(),
+            Action::SelectNext => self.select_next(),
+            Action::PageUp => self.select_by(-(self.page_size as isize)),
+            Action::PageDown => self.select_by(self.page_size as isize),
+            Action::SelectFirst => self.select(0),
+            Action::SelectLast => self.select(usize::MAX),
+            Action::CursorLeft => self.move_cursor_left(),
+            Action::CursorRight => self.move_cursor_right(),
+            Action::WordLeft => self.character_index = self.word_start(),
+            Action::WordRight => self.character_index = self.word_end(),
+            Action::CursorStart => self.character_index = 0,
+            Action::CursorEnd => self.character_index = self.input.chars().count(),
+            Action::DeleteBackward => self.delete_char(),
+            Action::DeleteForward => {
+                self.delete_range(self.character_index, self.character_index + 1)
+            }
+            Action::DeleteWord => self.delete_range(self.word_start(), self.character_index),
+            Action::DeleteToStart => self.delete_range(0, self.character_index),
+            Action::DeleteToEnd => self.delete_range(self.character_index, usize::MAX),
+            Action::None => {}
+        }
+
+        Ok(())
+    }
+
+    // selects a theme, the index is clamped to the matching themes
+    fn select(&mut self, index: usize) {
+        let last = self.matches.len().saturating_sub(1);
+        self.state.select(Some(index.min(last)));
+    }
+
+    fn select_by(&mut self, offset: isize) {
+        let index = self.state.selected().unwrap_or_default();
+        self.select(index.saturating_add_signed(offset));
     }
 
     fn select_previous(&mut self) {
-        self.state.select_previous();
+        self.select_by(-1);
     }
 
     fn select_next(&mut self) {
-        self.state.select_next();
+        self.select_by(1);
+    }
+
+    // selects a theme by its path, if it matches the filter
+    fn select_path(&mut self, path: &Path) {
+        if let Some(index) = self
+            .matches
+            .iter()
+            .position(|m| self.matched_theme(m).is_some_and(|t| t.path == path))
+        {
+            self.select(index);
+        }
+    }
+
+    fn toggle_favourite(&mut self) {
+        let Some(theme) = self.selected_theme() else {
+            return;
+        };
+
+        self.history.toggle_favourite(&theme.path);
+        if let Err(e) = self.history.save() {
+            self.notice = Some(format!("Failed to save the favourites: {e}"));
+        }
+
+        // the favourites are pinned, so the theme may move in the list
+        self.update_matches();
+        self.select_path(&theme.path);
+    }
+
+    // picks up the themes and the config changed on disk while the interface is open
+    fn refresh(&mut self) {
+        let Some(changes) = self.watcher.as_ref().map(Watcher::changes) else {
+            return;
+        };
+
+        if changes.config {
+            match self.config.reload_if_changed(&self.store) {
+                Ok(true) => {
+                    *self.original.lock().unwrap() = self.config.contents.clone();
+                    // the selected theme is written to the new contents
+                    if self.preview_mode.unwrap_or_default() == PreviewMode::Config {
+                        self.applied = None;
+                    }
+                }
+                Ok(false) => {}
+                Err(e) => self.notice = Some(format!("Failed to read the config: {e}")),
+            }
+        }
+
+        if changes.themes.is_empty() {
+            return;
+        }
+
+        let index = self.state.selected().unwrap_or_default();
+        let selected = self.selected_theme().map(|t| t.path);
+
+        self.store.rescan();
+        self.update_matches();
+
+        // keep the selected theme, or the same position when it was removed, the profiles keep
+        // their position
+        self.select(index);
+        let Some(path) = selected else {
+            return;
+        };
+        if !self.show_profiles {
+            self.select_path(&path);
+        }
+
+        // the selected theme was edited, the preview and the recolored terminal follow it
+        if changes.themes.contains(&path) {
+            self.preview = None;
+            if self.preview_mode == Some(PreviewMode::Osc) {
+                self.applied = None;
+            }
+        }
+    }
+
+    // selects the theme imported by the config, which needs no writing since it is already applied
+    fn select_current_theme(&mut self) {
+        let Some(path) = self.current_theme() else {
+            return;
+        };
+
+        self.select_path(&path);
+        if self.state.selected().is_some() {
+            self.applied = Some((path, None));
+        }
+    }
+
+    // selects a theme by its path, clearing the filter if it hides the theme
+    fn reveal_path(&mut self, path: &Path) {
+        // the theme is looked for among the themes rather than the profiles
+        if self.show_profiles {
+            self.show_profiles = false;
+            self.update_matches();
+        }
+
+        if !self
+            .matches
+            .iter()
+            .any(|m| self.store.themes[m.index].path == path)
+        {
+            self.input.clear();
+            self.character_index = 0;
+            self.update_matches();
+        }
+        self.select_path(path);
+    }
+
+    // selects the theme applied before the current one
+    fn select_previous_theme(&mut self) {
+        if let Some(theme) = self.previous_theme() {
+            self.reveal_path(&theme.path);
+        }
+    }
+
+    fn open_editor(&mut self) {
+        if let Some(theme) = self.selected_theme() {
+            self.editor = Some(Editor::new(theme));
+        }
+    }
+
+    fn close_editor(&mut self) {
+        self.editor = None;
+
+        // give the terminal the colors of the selected theme back
+        if self.preview_mode == Some(PreviewMode::Osc) {
+            self.applied = None;
+        }
+    }
+
+    fn handle_editor_key(&mut self, key: KeyEvent) {
+        let Some(editor) = &mut self.editor else {
+            return;
+        };
+
+        match editor.handle_key(key) {
+            Outcome::Continue => {}
+            // the edited colors are shown in the preview pane, and in the terminal with escape
+            // sequences when previewing that way
+            Outcome::Changed => {
+                if self.preview_mode == Some(PreviewMode::Osc) {
+                    if let Err(e) = osc::write(&osc::apply_sequence(&editor.palette)) {
+                        editor.error = Some(format!("Failed to preview the colors: {e}"));
+                    }
+                }
+            }
+            Outcome::Cancel => self.close_editor(),
+            Outcome::Save(name) => {
+                match self.store.check_writable().and_then(|_| editor.save(&name)) {
+                    Ok(path) => {
+                        self.close_editor();
+                        self.store.rescan();
+                        self.update_matches();
+                        self.reveal_path(&path);
+                    }
+                    Err(e) => editor.error = Some(format!("Failed to save the theme: {e}")),
+                }
+            }
+        }
+    }
+
+    // switches the list between the themes and the profiles, the filter applies to both
+    fn toggle_profiles(&mut self) {
+        if self.profiles.is_empty() && !self.show_profiles {
+            self.notice = Some("No profiles, they are defined in profiles.toml".to_string());
+            return;
+        }
+
+        self.show_profiles = !self.show_profiles;
+        self.update_matches();
+        self.select(0);
+    }
+
+    fn toggle_focus(&mut self) {
+        self.focus = match self.focus {
+            Focus::Filter => Focus::List,
+            Focus::List => Focus::Filter,
+        };
+    }
+
+    fn toggle_sort_mode(&mut self) {
+        self.sort_mode = self.sort_mode.next();
+        self.update_matches();
     }
 
     fn move_cursor_left(&mut self) {
@@ -100,6 +642,7 @@ impl ThemeChanger {
         let index = self.byte_index();
         self.input.insert(index, new_char);
         self.move_cursor_right();
+        self.update_matches();
     }
 
     fn delete_char(&mut self) {
@@ -115,6 +658,47 @@ impl ThemeChanger {
 
         self.input = before_char_to_delete.chain(after_char_to_delete).collect();
         self.move_cursor_left();
+        self.update_matches();
+    }
+
+    // removes the characters between two character indices and moves the cursor to the first one
+    fn delete_range(&mut self, start: usize, end: usize) {
+        let end = end.min(self.input.chars().count());
+        if start >= end {
+            return;
+        }
+
+        let before = self.input.chars().take(start);
+        let after = self.input.chars().skip(end);
+
+        self.input = before.chain(after).collect();
+        self.character_index = start;
+        self.update_matches();
+    }
+
+    // returns the index of the start of the word before the cursor
+    fn word_start(&self) -> usize {
+        let chars: Vec<_> = self.input.chars().take(self.character_index).collect();
+        let trimmed = chars.iter().rposition(|c| !c.is_whitespace());
+
+        match trimmed {
+            Some(last) => chars[..last]
+                .iter()
+                .rposition(|c| c.is_whitespace())
+                .map_or(0, |i| i + 1),
+            None => 0,
+        }
+    }
+
+    // returns the index of the end of the word after the cursor
+    fn word_end(&self) -> usize {
+        self.input
+            .chars()
+            .enumerate()
+            .skip(self.character_index)
+            .skip_while(|(_, c)| c.is_whitespace())
+            .find(|(_, c)| c.is_whitespace())
+            .map_or(self.input.chars().count(), |(i, _)| i)
     }
 
     fn byte_index(&self) -> usize {
@@ -129,248 +713,180 @@ impl ThemeChanger {
         new_cursor_pos.clamp(0, self.input.chars().count())
     }
 
-    fn exit(&mut self, restore_original: bool) {
+    fn exit(&mut self, restore_original: bool) -> Result<()> {
         self.exit = true;
 
+        // give the colors back to the terminal's config
+        if self.preview_mode == Some(PreviewMode::Osc) {
+            osc::write(&osc::reset_sequence())?;
+        }
+
         if restore_original {
-            let _ = fs::write(&self.config_path, self.config_table.to_string());
+            return self.config.restore();
         }
-    }
-}
 
-impl ThemeChanger {
-    // function adapted from alacritty's source
-    // https://github.com/alacritty/alacritty/blob/6fefa78eafa43f13998439cb9eaf15bc0441f004/alacritty/src/config/mod.rs#L378
-    fn find_config(&self) -> Result<PathBuf> {
-        let file_name = String::from("alacritty.toml");
-
-        let config_path = xdg::BaseDirectories::with_prefix("alacritty")
-            .ok()
-            .and_then(|xdg| xdg.find_config_file(&file_name))
-            .or_else(|| {
-                xdg::BaseDirectories::new()
-                    .ok()
-                    .and_then(|fallback| fallback.find_config_file(&file_name))
-            })
-            .or_else(|| {
-                if let Ok(home) = env::var("HOME") {
-                    // Fallback path: $HOME/.config/alacritty/alacritty.toml.
-                    let fallback = PathBuf::from(&home)
-                        .join(".config/alacritty")
-                        .join(&file_name);
-                    if fallback.exists() {
-                        return Some(fallback);
-                    }
-                    // Fallback path: $HOME/.alacritty.toml.
-                    let hidden_name = format!(".{file_name}");
-                    let fallback = PathBuf::from(&home).join(hidden_name);
-                    if fallback.exists() {
-                        return Some(fallback);
-                    }
+        match self.preview_mode.unwrap_or_default() {
+            PreviewMode::Config => {
+                // make sure the selected theme was written
+                self.apply_selection()?;
+            }
+            PreviewMode::Osc => {
+                if let Some(theme) = self.selected_theme() {
+                    let overrides = self.selected_overrides();
+                    self.config
+                        .apply_profile(&self.store, &theme.path, &overrides)?;
                 }
-                None
-            });
+            }
+        }
 
-        if let None = config_path {
-            return Err(anyhow!("Failed to find config file"));
+        if let Some(theme) = self.selected_theme() {
+            self.remember(&theme.path)?;
         }
 
-        Ok(config_path.unwrap())
+        Ok(())
     }
 
-    fn read_config(&mut self) -> Result<Table> {
-        let contents = fs::read_to_string(&self.config_path)?;
-        let config: Table = contents.parse()?;
+    // records an applied theme after the one it replaces, so `undo` can go back to it
+    fn remember(&mut self, path: &Path) -> Result<()> {
+        if let Some(current) = self.current_theme() {
+            self.history.push_recent(&current);
+        }
+        self.history.push_recent(path);
 
-        return Ok(config);
+        self.history.save()
     }
 
-    fn scan_themes(&self) -> Result<Vec<PathBuf>> {
-        let themes_dir = self.config_path.parent().unwrap().join("themes/themes");
-        let files = fs::read_dir(themes_dir)?;
+    // returns the most recently applied theme that isn't the current one
+    fn previous_theme(&self) -> Option<Theme> {
+        let current = self.current_theme();
 
-        let mut paths = files
-            .filter_map(|e| e.ok())
-            .map(|e| e.path())
-            .filter(|p| {
-                if let Ok(metadata) = p.metadata() {
-                    metadata.is_file()
-                } else {
-                    false
-                }
-            })
-            .filter(|p| p.to_string_lossy().ends_with(".toml"))
-            .collect::<Vec<_>>();
-
-        // sort the entries alphabetically
-        paths.sort_by(|a, b| b.cmp(a));
-
-        return Ok(paths);
+        self.history
+            .recent
+            .iter()
+            .filter(|p| Some(*p) != current.as_ref())
+            .find_map(|p| self.store.find_path(p))
+            .cloned()
     }
+}
 
+impl ThemeChanger {
     fn update_theme(&mut self) {
+        // errors are shown in the UI until a theme is applied
+        match self.apply_selection() {
+            Ok(()) => self.error = None,
+            Err(e) => self.error = Some(format!("Failed to apply the theme: {e}")),
+        }
+    }
+
+    fn apply_selection(&mut self) -> Result<()> {
         // select the first theme if no theme is selected
         if self.state.selected().is_none() {
             self.state.select_first();
         }
 
-        // get the selected theme index and return if no theme is selected
-        let index = self.state.selected();
-        if index.is_none() {
-            return;
-        }
-
-        let items = self.get_matched_themes();
+        // get the selected theme and return if no theme is selected
+        let Some(theme) = self.selected_theme() else {
+            return Ok(());
+        };
 
-        // check if the selected index is out of bounds
-        let index = index.unwrap();
-        if index >= items.len() {
-            return;
+        // the theme is only applied when the selection changes
+        let profile = self.selected_profile().map(|p| p.name.clone());
+        let selection = Some((theme.path.clone(), profile));
+        if self.applied == selection {
+            return Ok(());
         }
 
-        // get the selected theme
-        let theme = &items[index];
+        // write the updated config, or recolor the terminal, the options of the profiles can't be
+        // previewed with escape sequences
+        match self.preview_mode.unwrap_or_default() {
+            PreviewMode::Config => {
+                let overrides = self.selected_overrides();
+                self.config
+                    .apply_profile(&self.store, &theme.path, &overrides)?;
+            }
+            PreviewMode::Osc => {
+                let palette = theme.palette.clone().unwrap_or_default();
+                osc::write(&osc::apply_sequence(&palette))?;
+            }
+        }
+        self.applied = selection;
 
-        // clone to avoid mutating the original
-        let mut config_clone = self.config_table.clone();
+        Ok(())
+    }
 
-        // ensure the general section exists
-        if !config_clone.contains_key("general") {
-            config_clone.insert("general".to_string(), Value::Table(Table::new()));
+    // returns the theme of a listed theme or profile
+    fn matched_theme(&self, m: &Match) -> Option<&Theme> {
+        if self.show_profiles {
+            self.store.find(&self.profiles.get(m.index)?.theme)
+        } else {
+            self.store.themes.get(m.index)
         }
+    }
 
-        // retrieve the general section
-        let general = config_clone
-            .get_mut("general")
-            .expect("[general] does not exist")
-            .as_table_mut()
-            .expect("[general] is not a table");
+    fn selected_theme(&self) -> Option<Theme> {
+        let m = self.matches.get(self.state.selected()?)?;
 
-        // ensure the import array exists in the general section
-        if !general.contains_key("import") {
-            general.insert("import".to_string(), Value::Array(vec![]));
-        }
+        self.matched_theme(m).cloned()
+    }
 
-        // retrieve the import array from the general section
-        let import = general
-            .get_mut("import")
-            .expect("[import] does not exist")
-            .as_array_mut()
-            .expect("[import] is not an array");
+    // returns the selected profile, while the list shows the profiles
+    fn selected_profile(&self) -> Option<&Profile> {
+        if !self.show_profiles {
+            return None;
+        }
+        let m = self.matches.get(self.state.selected()?)?;
 
-        // clear the import array (if any) and push the selected theme
-        import.clear();
-        import.push(Value::String(theme.to_string_lossy().to_string()));
+        self.profiles.get(m.index)
+    }
 
-        // write the updated config
-        let _ = fs::write(&self.config_path, config_clone.to_string());
+    // returns the options set along with the selected theme
+    fn selected_overrides(&self) -> Vec<Override> {
+        self.selected_profile()
+            .map(|p| p.overrides.clone())
+            .unwrap_or_default()
     }
 
-    fn get_matched_themes(&self) -> Vec<PathBuf> {
-        let mut items: Vec<_> = self
-            .themes
-            .iter()
-            .zip(self.themes.iter())
-            .map(|(o, p)| (o, p.file_name().unwrap().to_str().unwrap().to_string()))
-            .map(|(o, p)| (o, p.replace(".toml", "")))
-            .map(|(o, p)| (o, SkimMatcherV2::default().fuzzy_match(&p, &self.input)))
-            .filter(|(_, m)| m.is_some())
-            .collect();
+    // reads the palette of the selected theme, only when the selection changes
+    fn update_preview(&mut self) {
+        let theme = self.selected_theme().map(|t| t.path);
 
-        items.sort_by_key(|(_, m)| m.unwrap());
+        if self.preview.as_ref().map(|(p, _)| p) == theme.as_ref() {
+            return;
+        }
 
-        return items.iter().rev().map(|(s, _)| s.to_path_buf()).collect();
+        self.preview = theme.map(|path| {
+            let palette = Palette::from_file(&path);
+            (path, palette)
+        });
     }
-}
 
-impl Widget for &mut ThemeChanger {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        let vertical = Layout::vertical([Constraint::Length(3), Constraint::Min(1)]);
-        let [input_area, messages_area] = vertical.areas(area);
+    // returns the theme imported by the config file as it was read
+    fn current_theme(&self) -> Option<PathBuf> {
+        self.config.current_theme(&self.store)
+    }
 
-        let horizontal =
-            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]);
-        let [left_area, right_area] = horizontal.areas(messages_area);
+    // matches the themes against the input, called whenever the input or the sort mode changes
+    pub(crate) fn update_matches(&mut self) {
+        // the profiles are listed in the order of the file
+        if self.show_profiles {
+            let names = self.profiles.iter().map(|p| p.name.as_str());
+            self.matches = self.matcher.match_names(names, &self.input);
+            return;
+        }
 
-        let input = Paragraph::new(self.input.as_str()).block(
-            Block::bordered()
-                .title("Filter")
-                .border_set(border::PLAIN)
-                .border_style(Style::default().fg(Color::Yellow)),
+        self.matches = self.matcher.matches(&self.store.themes, &self.input);
+        matcher::order(
+            &mut self.matches,
+            &self.store.themes,
+            self.sort_mode,
+            &self.history,
         );
-        input.render(input_area, buf);
+    }
 
-        let items = self.get_matched_themes();
-        let items: Vec<_> = items
+    fn get_matched_themes(&self) -> Vec<Theme> {
+        self.matches
             .iter()
-            .filter_map(|s| s.file_name())
-            .filter_map(|s| s.to_str())
-            .map(|s| s.replace(".toml", ""))
-            .collect();
-
-        let msg = vec![
-            "Press ".into(),
-            "esc".bold(),
-            " to exit, ".into(),
-            "Enter".bold(),
-            " to apply".into(),
-        ];
-
-        let list = List::new(items)
-            .block(
-                Block::bordered()
-                    .title("Themes")
-                    .title_bottom(msg)
-                    .border_set(border::PLAIN),
-            )
-            .highlight_style(Style::new().reversed())
-            .highlight_symbol("")
-            .repeat_highlight_symbol(true)
-            .direction(ListDirection::TopToBottom);
-
-        StatefulWidget::render(list, left_area, buf, &mut self.state);
-
-        let line1 = Line::from(vec![
-            " Default ".into(),
-            " Black ".fg(Color::Black),
-            " White ".fg(Color::White),
-            " Gray ".fg(Color::Gray),
-            " Red ".fg(Color::Red),
-            " Green ".fg(Color::Green),
-            " Yellow ".fg(Color::Yellow),
-            " Blue ".fg(Color::Blue),
-            " Magenta ".fg(Color::Magenta),
-            " Cyan ".fg(Color::Cyan),
-        ]);
-        let line2 = Line::from(vec![
-            " Default ".into(),
-            " Black ".bg(Color::Black),
-            " White ".bg(Color::White),
-            " Gray ".bg(Color::Gray),
-            " Red ".bg(Color::Red),
-            " Green ".bg(Color::Green),
-            " Yellow ".bg(Color::Yellow),
-            " Blue ".bg(Color::Blue),
-            " Magenta ".bg(Color::Magenta),
-            " Cyan ".bg(Color::Cyan),
-        ]);
-        let line3 = Line::from(vec![
-            " Default ".into(),
-            " Black ".fg(Color::Black).reversed(),
-            " White ".fg(Color::White).reversed(),
-            " Gray ".fg(Color::Gray).reversed(),
-            " Red ".fg(Color::Red).reversed(),
-            " Green ".fg(Color::Green).reversed(),
-            " Yellow ".fg(Color::Yellow).reversed(),
-            " Blue ".fg(Color::Blue).reversed(),
-            " Magenta ".fg(Color::Magenta).reversed(),
-            " Cyan ".fg(Color::Cyan).reversed(),
-        ]);
-
-        let block = Block::bordered().title("Preview").border_set(border::PLAIN);
-
-        Paragraph::new(Text::from(vec![line1, line2, line3]))
-            .block(block)
-            .render(right_area, buf);
+            .map(|m| self.store.themes[m.index].clone())
+            .collect()
     }
 }
@@ -0,0 +1,295 @@
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+use std::{
+    cmp::{Ordering, Reverse},
+    fmt,
+};
+
+use crate::{
+    history::History,
+    theme::{parse_filter, Theme},
+};
+
+// the order of the list, the best fuzzy matches still come first while filtering
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortMode {
+    #[default]
+    Name, // Alphabetical.
+    NameReverse, // Reverse alphabetical.
+    Contrast,    // Most readable theme first.
+    Luminance,   // Darkest background first.
+    Hue,         // Background hue around the color wheel, gray backgrounds last.
+    Recent,      // Most recently applied theme first.
+}
+
+impl SortMode {
+    pub fn next(self) -> SortMode {
+        match self {
+            SortMode::Name => SortMode::NameReverse,
+            SortMode::NameReverse => SortMode::Contrast,
+            SortMode::Contrast => SortMode::Luminance,
+            SortMode::Luminance => SortMode::Hue,
+            SortMode::Hue => SortMode::Recent,
+            SortMode::Recent => SortMode::Name,
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            SortMode::Name => "A-Z",
+            SortMode::NameReverse => "Z-A",
+            SortMode::Contrast => "by contrast",
+            SortMode::Luminance => "by luminance",
+            SortMode::Hue => "by hue",
+            SortMode::Recent => "by last used",
+        }
+    }
+}
+
+// compares values in ascending order, the missing ones last
+fn ascending(a: Option<f64>, b: Option<f64>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+// a theme, or a profile, matching the filter
+#[derive(Debug, Clone)]
+pub struct Match {
+    pub index: usize, // Index of the theme in the scanned themes, or of the profile.
+    pub score: i64,   // Fuzzy score, higher is better.
+    pub positions: Vec<usize>, // Indices of the matched characters in the name.
+}
+
+// wraps the fuzzy matcher so it is built once, `SkimMatcherV2` doesn't implement `Debug`
+#[derive(Default)]
+pub struct Matcher(SkimMatcherV2);
+
+impl fmt::Debug for Matcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Matcher")
+    }
+}
+
+impl Matcher {
+    // returns the themes matching the filter, best match first, the sort is stable so equal scores
+    // keep the order of the themes
+    pub fn matches(&self, themes: &[Theme], input: &str) -> Vec<Match> {
+        let (brightness, pattern) = parse_filter(input);
+
+        let mut matches: Vec<_> = themes
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| brightness.is_none() || t.brightness() == brightness)
+            .filter_map(|(index, t)| {
+                let (score, positions) = self.0.fuzzy_indices(&t.name, pattern)?;
+                Some(Match {
+                    index,
+                    score,
+                    positions,
+                })
+            })
+            .collect();
+
+        matches.sort_by_key(|m| Reverse(m.score));
+
+        matches
+    }
+
+    // returns the names matching the filter, such as the names of the profiles, best match first
+    pub fn match_names<'a>(
+        &self,
+        names: impl IntoIterator<Item = &'a str>,
+        input: &str,
+    ) -> Vec<Match> {
+        let mut matches: Vec<_> = names
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, name)| {
+                let (score, positions) = self.0.fuzzy_indices(name, input)?;
+                Some(Match {
+                    index,
+                    score,
+                    positions,
+                })
+            })
+            .collect();
+
+        matches.sort_by_key(|m| Reverse(m.score));
+
+        matches
+    }
+}
+
+// orders the matches by best match, then pins the favourites, and the recent themes from the most
+// recent, above the themes matching as well, then orders the rest by the sort mode
+pub fn order(matches: &mut [Match], themes: &[Theme], sort_mode: SortMode, history: &History) {
+    // the best matches come first in every mode, the pins and the mode order the themes matching
+    // as well, which are all of them when there is no filter, the sort is stable, so themes
+    // sorting the same keep their match order, which is alphabetical
+    let theme = |m: &Match| &themes[m.index];
+    let pinned = |m: &Match| {
+        let path = &theme(m).path;
+        if history.is_favourite(path) {
+            0
+        } else {
+            history
+                .pinned_recent()
+                .iter()
+                .position(|p| p == path)
+                .map_or(usize::MAX, |i| i + 1)
+        }
+    };
+    let recent = |m: &Match| {
+        history
+            .recent
+            .iter()
+            .position(|p| *p == theme(m).path)
+            .unwrap_or(usize::MAX)
+    };
+    let by_mode = |a: &Match, b: &Match| match sort_mode {
+        SortMode::Name => theme(a).name.cmp(&theme(b).name),
+        SortMode::NameReverse => theme(b).name.cmp(&theme(a).name),
+        SortMode::Contrast => {
+            let a = theme(a).min_contrast();
+            let b = theme(b).min_contrast();
+            b.unwrap_or(0.0).total_cmp(&a.unwrap_or(0.0))
+        }
+        SortMode::Luminance => ascending(theme(a).luminance(), theme(b).luminance()),
+        SortMode::Hue => ascending(theme(a).hue(), theme(b).hue()),
+        SortMode::Recent => recent(a).cmp(&recent(b)),
+    };
+    matches.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| pinned(a).cmp(&pinned(b)))
+            .then_with(|| by_mode(a, b))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::palette::Palette;
+    use std::path::PathBuf;
+
+    // themes with the given background, named after their order in the file
+    fn themes() -> Vec<Theme> {
+        [
+            ("apricot", "#fdf6e3"),
+            ("blossom", "#2b1d24"),
+            ("coal", "#000000"),
+            ("dusk", "#282a36"),
+        ]
+        .into_iter()
+        .map(|(name, background)| Theme {
+            name: name.to_string(),
+            path: PathBuf::from(format!("/themes/{name}.toml")),
+            source: PathBuf::from("/themes"),
+            palette: Palette::parse(&format!(
+                "[colors.primary]\nbackground = \"{background}\"\nforeground = \"#aaaaaa\"\n"
+            ))
+            .ok(),
+            problems: Vec::new(),
+        })
+        .collect()
+    }
+
+    fn ordered(input: &str, sort_mode: SortMode, history: &History) -> Vec<String> {
+        let themes = themes();
+        let mut matches = Matcher::default().matches(&themes, input);
+        order(&mut matches, &themes, sort_mode, history);
+
+        matches
+            .iter()
+            .map(|m| themes[m.index].name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn cycle_sort_modes() {
+        let mut sort_mode = SortMode::default();
+        let mut titles = Vec::new();
+        for _ in 0..6 {
+            titles.push(sort_mode.title());
+            sort_mode = sort_mode.next();
+        }
+
+        assert_eq!(sort_mode, SortMode::Name);
+        assert_eq!(
+            titles,
+            [
+                "A-Z",
+                "Z-A",
+                "by contrast",
+                "by luminance",
+                "by hue",
+                "by last used"
+            ]
+        );
+    }
+
+    #[test]
+    fn sort_by_mode() {
+        let history = History::default();
+
+        assert_eq!(
+            ordered("", SortMode::Name, &history),
+            ["apricot", "blossom", "coal", "dusk"]
+        );
+        assert_eq!(
+            ordered("", SortMode::NameReverse, &history),
+            ["dusk", "coal", "blossom", "apricot"]
+        );
+        assert_eq!(
+            ordered("", SortMode::Luminance, &history),
+            ["coal", "blossom", "dusk", "apricot"]
+        );
+        // the gray backgrounds come last
+        assert_eq!(
+            ordered("", SortMode::Hue, &history),
+            ["apricot", "dusk", "blossom", "coal"]
+        );
+    }
+
+    #[test]
+    fn sort_by_best_match_while_filtering() {
+        let history = History::default();
+
+        assert_eq!(
+            ordered("co", SortMode::NameReverse, &history),
+            ["coal", "apricot"]
+        );
+        // the other modes only order the themes matching as well, apricot has the first hue
+        assert_eq!(ordered("co", SortMode::Hue, &history), ["coal", "apricot"]);
+    }
+
+    #[test]
+    fn sort_by_last_used() {
+        let mut history = History::default();
+        for name in ["blossom", "coal", "dusk", "apricot", "dusk"] {
+            history.push_recent(&PathBuf::from(format!("/themes/{name}.toml")));
+        }
+
+        assert_eq!(
+            ordered("", SortMode::Recent, &history),
+            ["dusk", "apricot", "coal", "blossom"]
+        );
+    }
+
+    #[test]
+    fn pin_only_among_equal_matches() {
+        let mut history = History::default();
+        history.toggle_favourite(&PathBuf::from("/themes/dusk.toml"));
+        history.push_recent(&PathBuf::from("/themes/apricot.toml"));
+
+        assert_eq!(
+            ordered("", SortMode::Name, &history),
+            ["dusk", "apricot", "blossom", "coal"]
+        );
+        // the pinned themes don't go above better matches
+        assert_eq!(ordered("co", SortMode::Name, &history), ["coal", "apricot"]);
+    }
+}
@@ -0,0 +1,58 @@
+use clap::ValueEnum;
+use std::io::{self, Write};
+
+use crate::palette::{Palette, Rgb};
+
+// how the selected theme is previewed
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PreviewMode {
+    /// Rewrite the config, every alacritty window follows the selection
+    #[default]
+    Config,
+    /// Recolor the current terminal with escape sequences, the config is written on Enter
+    Osc,
+}
+
+// OSC 10, 11 and 12 set the foreground, background and cursor colors, OSC 4 sets the ansi colors
+// https://invisible-island.net/xterm/ctlseqs/ctlseqs.html#h3-Operating-System-Commands
+fn color(rgb: Rgb) -> String {
+    format!("rgb:{:02x}/{:02x}/{:02x}", rgb.r, rgb.g, rgb.b)
+}
+
+// returns the sequences applying a palette, colors the palette doesn't define are reset
+pub fn apply_sequence(palette: &Palette) -> String {
+    let mut sequence = String::new();
+
+    let dynamic = [
+        (10, palette.foreground),
+        (11, palette.background),
+        (12, palette.cursor),
+    ];
+    for (code, rgb) in dynamic {
+        match rgb {
+            Some(rgb) => sequence.push_str(&format!("\x1b]{code};{}\x1b\\", color(rgb))),
+            None => sequence.push_str(&format!("\x1b]{}\x1b\\", code + 100)),
+        }
+    }
+
+    let ansi = palette.normal.iter().chain(palette.bright.iter());
+    for (index, rgb) in ansi.enumerate() {
+        match rgb {
+            Some(rgb) => sequence.push_str(&format!("\x1b]4;{index};{}\x1b\\", color(*rgb))),
+            None => sequence.push_str(&format!("\x1b]104;{index}\x1b\\")),
+        }
+    }
+
+    sequence
+}
+
+// returns the sequences resetting every color to the one of the terminal's config
+pub fn reset_sequence() -> String {
+    "\x1b]104\x1b\\\x1b]110\x1b\\\x1b]111\x1b\\\x1b]112\x1b\\".to_string()
+}
+
+pub fn write(sequence: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    stdout.write_all(sequence.as_bytes())?;
+    stdout.flush()
+}
@@ -0,0 +1,292 @@
+use anyhow::Result;
+use ratatui::style::Color;
+use std::{fmt, path::Path};
+use toml_edit::{value, DocumentMut, Item, Table};
+
+use crate::theme::read_theme;
+
+// minimum contrast ratio of normal text to pass WCAG level AA
+pub const AA_CONTRAST: f64 = 4.5;
+
+// backgrounds brighter than this have more contrast with black text than with white text
+pub const LIGHT_LUMINANCE: f64 = 0.179;
+
+// names of the 8 ansi colors, in the order used by the normal and bright tables
+pub const ANSI_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+// returns the index of an ansi color name in the normal and bright tables
+fn ansi_index(name: &str) -> Option<usize> {
+    ANSI_NAMES.iter().position(|n| *n == name)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    pub fn new(r: u8, g: u8, b: u8) -> Self {
+        Rgb { r, g, b }
+    }
+
+    // parses a color in one of the formats accepted by alacritty: `#rrggbb` or `0xrrggbb`
+    pub fn parse(value: &str) -> Option<Rgb> {
+        let value = value.trim();
+        let hex = value
+            .strip_prefix('#')
+            .or_else(|| value.strip_prefix("0x"))
+            .or_else(|| value.strip_prefix("0X"))?;
+
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+
+        Some(Rgb::new(channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    // relative luminance as defined by WCAG, from 0 (black) to 1 (white)
+    // https://www.w3.org/TR/WCAG21/#dfn-relative-luminance
+    pub fn luminance(&self) -> f64 {
+        let linear = |c: u8| {
+            let c = c as f64 / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+
+        0.2126 * linear(self.r) + 0.7152 * linear(self.g) + 0.0722 * linear(self.b)
+    }
+
+    // contrast ratio as defined by WCAG, from 1 (same color) to 21 (black on white)
+    // https://www.w3.org/TR/WCAG21/#dfn-contrast-ratio
+    pub fn contrast(&self, other: &Rgb) -> f64 {
+        let (a, b) = (self.luminance(), other.luminance());
+
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    // hue in degrees, saturation and lightness from 0 to 1
+    pub fn to_hsl(self) -> (f64, f64, f64) {
+        let [r, g, b] = [self.r, self.g, self.b].map(|c| c as f64 / 255.0);
+        let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+        let lightness = (max + min) / 2.0;
+
+        if max == min {
+            return (0.0, 0.0, lightness);
+        }
+
+        let delta = max - min;
+        let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+        let hue = if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+
+        (hue, saturation, lightness)
+    }
+
+    pub fn from_hsl(hue: f64, saturation: f64, lightness: f64) -> Rgb {
+        let (saturation, lightness) = (saturation.clamp(0.0, 1.0), lightness.clamp(0.0, 1.0));
+        let hue = hue.rem_euclid(360.0) / 60.0;
+
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let x = chroma * (1.0 - (hue.rem_euclid(2.0) - 1.0).abs());
+        let (r, g, b) = match hue as u8 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+
+        let m = lightness - chroma / 2.0;
+        let channel = |c: f64| ((c + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+
+        Rgb::new(channel(r), channel(g), channel(b))
+    }
+}
+
+impl fmt::Display for Rgb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+impl From<Rgb> for Color {
+    fn from(rgb: Rgb) -> Self {
+        Color::Rgb(rgb.r, rgb.g, rgb.b)
+    }
+}
+
+// the colors defined by a theme file, any of them can be missing
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Palette {
+    pub background: Option<Rgb>,
+    pub foreground: Option<Rgb>,
+    pub normal: [Option<Rgb>; 8],
+    pub bright: [Option<Rgb>; 8],
+    pub cursor_text: Option<Rgb>,
+    pub cursor: Option<Rgb>,
+    pub selection_text: Option<Rgb>,
+    pub selection_background: Option<Rgb>,
+}
+
+impl Palette {
+    pub fn from_file(path: &Path) -> Result<Palette> {
+        let contents = read_theme(path)?;
+
+        Palette::parse(&contents)
+    }
+
+    pub fn parse(contents: &str) -> Result<Palette> {
+        let doc: DocumentMut = contents.parse()?;
+        let colors = doc.get("colors");
+
+        let mut palette = Palette::default();
+
+        // looks up `colors.<table>.<key>`, values such as `CellForeground` are not colors and are skipped
+        for (table, key) in Palette::slots() {
+            let color = colors
+                .and_then(|c| c.get(table))
+                .and_then(|t| t.get(key))
+                .and_then(Item::as_str)
+                .and_then(Rgb::parse);
+
+            palette.set(table, key, color);
+        }
+
+        Ok(palette)
+    }
+
+    // every color of a palette as a `(table, key)` pair of the `colors` section, in file order
+    pub fn slots() -> Vec<(&'static str, &'static str)> {
+        let mut slots = vec![
+            ("primary", "background"),
+            ("primary", "foreground"),
+            ("cursor", "text"),
+            ("cursor", "cursor"),
+            ("selection", "text"),
+            ("selection", "background"),
+        ];
+        slots.extend(ANSI_NAMES.map(|name| ("normal", name)));
+        slots.extend(ANSI_NAMES.map(|name| ("bright", name)));
+
+        slots
+    }
+
+    pub fn get(&self, table: &str, key: &str) -> Option<Rgb> {
+        match (table, key) {
+            ("primary", "background") => self.background,
+            ("primary", "foreground") => self.foreground,
+            ("cursor", "text") => self.cursor_text,
+            ("cursor", "cursor") => self.cursor,
+            ("selection", "text") => self.selection_text,
+            ("selection", "background") => self.selection_background,
+            ("normal", _) => self.normal[ansi_index(key)?],
+            ("bright", _) => self.bright[ansi_index(key)?],
+            _ => None,
+        }
+    }
+
+    // sets a color, unknown slots are ignored
+    pub fn set(&mut self, table: &str, key: &str, color: Option<Rgb>) {
+        let slot = match (table, key) {
+            ("primary", "background") => &mut self.background,
+            ("primary", "foreground") => &mut self.foreground,
+            ("cursor", "text") => &mut self.cursor_text,
+            ("cursor", "cursor") => &mut self.cursor,
+            ("selection", "text") => &mut self.selection_text,
+            ("selection", "background") => &mut self.selection_background,
+            ("normal", _) => match ansi_index(key) {
+                Some(index) => &mut self.normal[index],
+                None => return,
+            },
+            ("bright", _) => match ansi_index(key) {
+                Some(index) => &mut self.bright[index],
+                None => return,
+            },
+            _ => return,
+        };
+
+        *slot = color;
+    }
+
+    // slots that have no color, formatted as `table.key`
+    pub fn missing(&self) -> Vec<String> {
+        Palette::slots()
+            .into_iter()
+            .filter(|(table, key)| self.get(table, key).is_none())
+            .map(|(table, key)| format!("{table}.{key}"))
+            .collect()
+    }
+
+    // serializes the palette as an alacritty theme file
+    pub fn to_toml(&self) -> String {
+        let mut colors = Table::new();
+        colors.set_implicit(true);
+
+        for (table, key) in Palette::slots() {
+            let Some(color) = self.get(table, key) else {
+                continue;
+            };
+
+            colors
+                .entry(table)
+                .or_insert(Item::Table(Table::new()))
+                .as_table_mut()
+                .expect("color section is not a table")
+                .insert(key, value(color.to_string()));
+        }
+
+        let mut doc = DocumentMut::new();
+        doc.insert("colors", Item::Table(colors));
+
+        doc.to_string()
+    }
+
+    // contrast of a color against the primary background
+    pub fn contrast(&self, color: Option<Rgb>) -> Option<f64> {
+        Some(color?.contrast(&self.background?))
+    }
+
+    // lowest contrast against the background among the colors used for text, with the name of the color
+    pub fn min_contrast(&self) -> Option<(f64, String)> {
+        let background = self.background?;
+
+        // normal black on dark themes (white on light ones) is usually a shade of the background
+        // rather than a text color, so it is left out
+        let skipped = if background.luminance() > LIGHT_LUMINANCE {
+            "white"
+        } else {
+            "black"
+        };
+
+        let normal = ANSI_NAMES
+            .iter()
+            .zip(self.normal)
+            .filter(|(name, _)| **name != skipped)
+            .map(|(name, color)| (format!("normal {name}"), color));
+        let bright = ANSI_NAMES
+            .iter()
+            .zip(self.bright)
+            .map(|(name, color)| (format!("bright {name}"), color));
+
+        std::iter::once(("foreground".to_string(), self.foreground))
+            .chain(normal)
+            .chain(bright)
+            .filter_map(|(name, color)| Some((self.contrast(color)?, name)))
+            .min_by(|(a, _), (b, _)| a.total_cmp(b))
+    }
+}
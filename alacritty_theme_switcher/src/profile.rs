@@ -0,0 +1,146 @@
+use anyhow::{anyhow, Result};
+use std::{fs, path::Path};
+use toml_edit::{DocumentMut, Item, Value};
+
+// a config option set by a profile, with the path of its key
+pub type Override = (Vec<String>, Value);
+
+// a theme along with the config options changed with it, such as the font size or the opacity
+#[derive(Debug, Clone)]
+pub struct Profile {
+    pub name: String,             // Name of the profile, the key of its table.
+    pub theme: String,            // Name of the theme imported by the profile.
+    pub overrides: Vec<Override>, // Options set in the config.
+}
+
+impl Profile {
+    // the options as they are written in the config, e.g. `font.size = 13`
+    pub fn describe_overrides(&self) -> Vec<String> {
+        self.overrides
+            .iter()
+            .map(|(key, value)| format!("{} = {}", key.join("."), value.to_string().trim()))
+            .collect()
+    }
+}
+
+// reads the profiles of `$XDG_CONFIG_HOME/alacritty_theme_switcher/profiles.toml`, there are none
+// without the file
+pub fn load_profiles() -> Result<Vec<Profile>> {
+    let path = xdg::BaseDirectories::with_prefix("alacritty_theme_switcher")
+        .ok()
+        .and_then(|xdg| xdg.find_config_file("profiles.toml"));
+
+    match path {
+        Some(path) => read_profiles(&path),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn read_profiles(path: &Path) -> Result<Vec<Profile>> {
+    let contents = fs::read_to_string(path)?;
+    parse_profiles(&contents).map_err(|e| anyhow!("Failed to parse {}: {e}", path.display()))
+}
+
+// reads profiles such as
+//
+//     [work]
+//     theme = "solarized_light"
+//     font.size = 13
+//     window.opacity = 1.0
+//     window.padding = { x = 12, y = 12 }
+//
+// in the order of the file, every option other than `theme` is set in the config
+pub fn parse_profiles(contents: &str) -> Result<Vec<Profile>> {
+    let doc: DocumentMut = contents.parse()?;
+    let mut profiles = Vec::new();
+
+    for (name, item) in doc.iter() {
+        let table = item
+            .as_table_like()
+            .ok_or_else(|| anyhow!("Profile `{name}` must be a table"))?;
+
+        let theme = table
+            .get("theme")
+            .and_then(Item::as_str)
+            .ok_or_else(|| anyhow!("Profile `{name}` has no `theme`"))?;
+
+        let mut overrides = Vec::new();
+        for (key, item) in table.iter().filter(|(key, _)| *key != "theme") {
+            flatten(vec![key.to_string()], item, &mut overrides)
+                .map_err(|e| anyhow!("Profile `{name}`: {e}"))?;
+        }
+
+        profiles.push(Profile {
+            name: name.to_string(),
+            theme: theme.to_string(),
+            overrides,
+        });
+    }
+
+    Ok(profiles)
+}
+
+// lists the values of the nested tables with their key path, so setting `window.padding.x` keeps
+// the other options of `[window]`
+fn flatten(key: Vec<String>, item: &Item, overrides: &mut Vec<Override>) -> Result<()> {
+    if let Some(table) = item.as_table_like() {
+        for (name, item) in table.iter() {
+            let mut key = key.clone();
+            key.push(name.to_string());
+            flatten(key, item, overrides)?;
+        }
+        return Ok(());
+    }
+
+    let mut value = item
+        .as_value()
+        .cloned()
+        .ok_or_else(|| anyhow!("`{}` must be a value or a table", key.join(".")))?;
+    // the formatting of the profiles file isn't carried to the config
+    value.decor_mut().clear();
+    overrides.push((key, value));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_overrides() {
+        let profiles = parse_profiles(
+            "\
+[work]
+theme = \"solarized_light\"
+font.size = 13 # larger
+window.opacity = 1.0
+window.padding = { x = 12, y = 12 }
+
+[night]
+theme = \"dracula\"
+",
+        )
+        .unwrap();
+
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].name, "work");
+        assert_eq!(profiles[0].theme, "solarized_light");
+        assert_eq!(
+            profiles[0].describe_overrides(),
+            [
+                "font.size = 13",
+                "window.opacity = 1.0",
+                "window.padding.x = 12",
+                "window.padding.y = 12"
+            ]
+        );
+        assert!(profiles[1].overrides.is_empty());
+    }
+
+    #[test]
+    fn reject_profiles_without_theme() {
+        assert!(parse_profiles("[work]\nfont.size = 13\n").is_err());
+        assert!(parse_profiles("work = \"dracula\"\n").is_err());
+    }
+}
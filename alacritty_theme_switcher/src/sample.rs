@@ -0,0 +1,238 @@
+use alacritty_theme_switcher::palette::{Palette, Rgb};
+use ratatui::{
+    style::Stylize,
+    text::{Line, Span},
+};
+
+// indices of the ansi colors in `Palette::normal` and `Palette::bright`
+const BLACK: usize = 0;
+const RED: usize = 1;
+const GREEN: usize = 2;
+const YELLOW: usize = 3;
+const BLUE: usize = 4;
+const MAGENTA: usize = 5;
+const CYAN: usize = 6;
+
+// content drawn with the colors of the selected theme, to see how it looks on real work
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sample {
+    #[default]
+    Shell, // A shell session with a prompt and compiler output.
+    Code,    // A syntax highlighted code snippet.
+    Diff,    // The output of `git diff`.
+    Listing, // The output of `ls --color`.
+}
+
+impl Sample {
+    pub fn next(self) -> Sample {
+        match self {
+            Sample::Shell => Sample::Code,
+            Sample::Code => Sample::Diff,
+            Sample::Diff => Sample::Listing,
+            Sample::Listing => Sample::Shell,
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Sample::Shell => "shell",
+            Sample::Code => "code",
+            Sample::Diff => "git diff",
+            Sample::Listing => "ls --color",
+        }
+    }
+
+    // the sample colored with the palette, the text without a color uses the theme foreground
+    pub fn lines(self, palette: &Palette) -> Vec<Line<'static>> {
+        let p = Painter(palette);
+
+        match self {
+            Sample::Shell => shell(&p),
+            Sample::Code => code(&p),
+            Sample::Diff => diff(&p),
+            Sample::Listing => listing(&p),
+        }
+    }
+}
+
+// colors pieces of text with the ansi colors of a palette, the missing colors are left unset
+struct Painter<'a>(&'a Palette);
+
+impl Painter<'_> {
+    fn paint(color: Option<Rgb>, text: &str) -> Span<'static> {
+        let span = Span::raw(text.to_string());
+        match color {
+            Some(color) => span.fg(color),
+            None => span,
+        }
+    }
+
+    fn normal(&self, index: usize, text: &str) -> Span<'static> {
+        Painter::paint(self.0.normal[index], text)
+    }
+
+    fn bright(&self, index: usize, text: &str) -> Span<'static> {
+        Painter::paint(self.0.bright[index], text)
+    }
+}
+
+fn shell(p: &Painter) -> Vec<Line<'static>> {
+    let prompt = |command: &str| {
+        Line::from(vec![
+            p.normal(BLUE, "~/code/site").bold(),
+            " on ".into(),
+            p.normal(MAGENTA, "main").bold(),
+            p.normal(GREEN, " ❯ ").bold(),
+            command.to_string().into(),
+        ])
+    };
+
+    vec![
+        prompt("cargo build"),
+        Line::from(vec![
+            p.normal(GREEN, "   Compiling").bold(),
+            " site v0.1.0".into(),
+        ]),
+        Line::from(vec![
+            p.normal(YELLOW, "warning").bold(),
+            ": unused variable: `port`".bold(),
+        ]),
+        Line::from(vec![
+            p.normal(BLUE, " --> ").bold(),
+            "src/main.rs:12:9".into(),
+        ]),
+        Line::from(vec![
+            p.normal(RED, "error[E0425]").bold(),
+            ": cannot find value `config`".bold(),
+        ]),
+        Line::from(vec![
+            p.normal(RED, "error").bold(),
+            ": could not compile `site`".bold(),
+        ]),
+        prompt("echo $SHELL"),
+        "/usr/bin/zsh".into(),
+        Line::from(vec![
+            p.normal(BLUE, "~/code/site").bold(),
+            " on ".into(),
+            p.normal(MAGENTA, "main").bold(),
+            p.normal(RED, " ❯ ").bold(),
+            p.bright(BLACK, "# the prompt turns red after an error"),
+        ]),
+    ]
+}
+
+fn code(p: &Painter) -> Vec<Line<'static>> {
+    let comment = |text: &str| p.bright(BLACK, text).italic();
+    let keyword = |text: &str| p.normal(MAGENTA, text);
+    let function = |text: &str| p.normal(BLUE, text);
+    let kind = |text: &str| p.normal(YELLOW, text);
+    let string = |text: &str| p.normal(GREEN, text);
+    let number = |text: &str| p.bright(RED, text);
+    let constant = |text: &str| p.normal(CYAN, text);
+
+    vec![
+        Line::from(comment("// reads the port from the environment")),
+        Line::from(vec![
+            keyword("fn "),
+            function("port"),
+            "() -> ".into(),
+            kind("Result"),
+            "<".into(),
+            kind("u16"),
+            ", ".into(),
+            kind("Error"),
+            "> {".into(),
+        ]),
+        Line::from(vec![
+            keyword("    let "),
+            "value = env::".into(),
+            function("var"),
+            "(".into(),
+            string("\"PORT\""),
+            ")?;".into(),
+        ]),
+        Line::from(vec![
+            keyword("    match "),
+            "value.".into(),
+            function("parse"),
+            "::<".into(),
+            kind("u16"),
+            ">() {".into(),
+        ]),
+        Line::from(vec![
+            constant("        Ok"),
+            "(port) ".into(),
+            keyword("if "),
+            "port > ".into(),
+            number("1024"),
+            " => ".into(),
+            constant("Ok"),
+            "(port),".into(),
+        ]),
+        Line::from(vec![
+            "        _ => ".into(),
+            constant("Err"),
+            "(".into(),
+            kind("Error"),
+            "::".into(),
+            constant("InvalidPort"),
+            "(value)),".into(),
+        ]),
+        "    }".into(),
+        "}".into(),
+    ]
+}
+
+fn diff(p: &Painter) -> Vec<Line<'static>> {
+    vec![
+        "diff --git a/src/main.rs b/src/main.rs".bold().into(),
+        "index 3b18e51..a9c2f4d 100644".bold().into(),
+        "--- a/src/main.rs".bold().into(),
+        "+++ b/src/main.rs".bold().into(),
+        Line::from(vec![
+            p.normal(CYAN, "@@ -9,7 +9,7 @@"),
+            " fn main() -> Result<()> {".into(),
+        ]),
+        "     let config = Config::load()?;".into(),
+        p.normal(RED, "-    let port = 8080;").into(),
+        p.normal(GREEN, "+    let port = port()?;").into(),
+        "     serve(config, port)".into(),
+        "".into(),
+        Line::from(vec![
+            p.normal(YELLOW, "commit 9fceb02"),
+            " (".into(),
+            p.normal(CYAN, "HEAD -> ").bold(),
+            p.normal(GREEN, "main").bold(),
+            ", ".into(),
+            p.normal(RED, "origin/main").bold(),
+            ")".into(),
+        ]),
+    ]
+}
+
+// the colors of the default `LS_COLORS` of GNU coreutils
+fn listing(p: &Painter) -> Vec<Line<'static>> {
+    let entry =
+        |mode: &str, name: Span<'static>| Line::from(vec![format!("{mode}  ").into(), name]);
+
+    vec![
+        entry("drwxr-xr-x", p.normal(BLUE, "assets").bold()),
+        entry("drwxr-xr-x", p.normal(BLUE, "src").bold()),
+        entry("-rwxr-xr-x", p.normal(GREEN, "build.sh").bold()),
+        entry("-rw-r--r--", "Cargo.toml".into()),
+        entry("-rw-r--r--", "README.md".into()),
+        entry("-rw-r--r--", p.normal(MAGENTA, "logo.png").bold()),
+        entry("-rw-r--r--", p.normal(RED, "release.tar.gz").bold()),
+        Line::from(vec![
+            "lrwxrwxrwx  ".into(),
+            p.normal(CYAN, "latest").bold(),
+            " -> target/release".into(),
+        ]),
+        Line::from(vec![
+            "lrwxrwxrwx  ".into(),
+            p.normal(RED, "old").bold(),
+            " -> missing".into(),
+        ]),
+        entry("prw-r--r--", p.normal(YELLOW, "events")),
+    ]
+}
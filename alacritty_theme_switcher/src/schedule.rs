@@ -0,0 +1,151 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone};
+use toml_edit::{Item, TableLike};
+
+// when a scheduled theme starts
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum At {
+    Time(NaiveTime),
+    Sunrise,
+    Sunset,
+}
+
+// the `[schedule]` table of the settings file, mapping times of the day to themes
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule {
+    coordinates: Option<(f64, f64)>, // Latitude and longitude, used for sunrise and sunset.
+    entries: Vec<(At, String)>,      // Themes and the time they start at.
+}
+
+impl Schedule {
+    // reads a table such as
+    //
+    //     [schedule]
+    //     latitude = 48.85
+    //     longitude = 2.35
+    //
+    //     [schedule.themes]
+    //     sunrise = "solarized_light"
+    //     "21:00" = "dracula"
+    pub fn parse(table: &dyn TableLike) -> Result<Schedule> {
+        let float = |key: &str| -> Result<Option<f64>> {
+            match table.get(key) {
+                Some(item) => item
+                    .as_float()
+                    .or_else(|| item.as_integer().map(|i| i as f64))
+                    .map(Some)
+                    .ok_or_else(|| anyhow!("`{key}` must be a number")),
+                None => Ok(None),
+            }
+        };
+
+        let coordinates = match (float("latitude")?, float("longitude")?) {
+            (Some(latitude), Some(longitude)) => Some((latitude, longitude)),
+            (None, None) => None,
+            _ => return Err(anyhow!("`latitude` and `longitude` must be given together")),
+        };
+
+        let themes = table
+            .get("themes")
+            .and_then(Item::as_table_like)
+            .ok_or_else(|| anyhow!("Missing the `[schedule.themes]` table"))?;
+
+        let mut entries = vec![];
+        for (key, theme) in themes.iter() {
+            let at = match key {
+                "sunrise" => At::Sunrise,
+                "sunset" => At::Sunset,
+                _ => At::Time(
+                    NaiveTime::parse_from_str(key, "%H:%M")
+                        .map_err(|_| anyhow!("Invalid time `{key}`, expected `HH:MM`"))?,
+                ),
+            };
+
+            if matches!(at, At::Sunrise | At::Sunset) && coordinates.is_none() {
+                return Err(anyhow!("`{key}` needs `latitude` and `longitude`"));
+            }
+
+            let theme = theme
+                .as_str()
+                .ok_or_else(|| anyhow!("The theme of `{key}` must be a string"))?;
+            entries.push((at, theme.to_string()));
+        }
+
+        Ok(Schedule {
+            coordinates,
+            entries,
+        })
+    }
+
+    // returns the themes of a day with their start time, sorted by time, sunrise and sunset are
+    // skipped on days the sun doesn't rise or set
+    fn day(&self, date: NaiveDate) -> Vec<(NaiveTime, &str)> {
+        let sun = self
+            .coordinates
+            .and_then(|(latitude, longitude)| sun_times(date, latitude, longitude));
+
+        let mut day: Vec<_> = self
+            .entries
+            .iter()
+            .filter_map(|(at, theme)| {
+                let time = match at {
+                    At::Time(time) => *time,
+                    At::Sunrise => sun?.0,
+                    At::Sunset => sun?.1,
+                };
+                Some((time, theme.as_str()))
+            })
+            .collect();
+
+        day.sort_by_key(|(time, _)| *time);
+
+        day
+    }
+
+    // returns the theme scheduled at the given time, which is the last one that started, or the
+    // last one of the previous day before the first theme of the day starts
+    pub fn theme_at(&self, now: DateTime<Local>) -> Option<&str> {
+        let today = self.day(now.date_naive());
+        if let Some((_, theme)) = today.iter().rev().find(|(time, _)| *time <= now.time()) {
+            return Some(theme);
+        }
+
+        let yesterday = now.date_naive().pred_opt()?;
+        self.day(yesterday).last().map(|(_, theme)| *theme)
+    }
+}
+
+// computes the local sunrise and sunset times with the sunrise equation, returns `None` during the
+// polar day and night
+// https://en.wikipedia.org/wiki/Sunrise_equation
+fn sun_times(date: NaiveDate, latitude: f64, longitude: f64) -> Option<(NaiveTime, NaiveTime)> {
+    let (sin, cos) = (|d: f64| d.to_radians().sin(), |d: f64| d.to_radians().cos());
+
+    // days since 2000-01-01 12:00 UTC, at the mean solar noon of the longitude
+    let j2000 = NaiveDate::from_ymd_opt(2000, 1, 1)?;
+    let days = (date - j2000).num_days() as f64 + 0.0008 - longitude / 360.0;
+
+    let anomaly = (357.5291 + 0.98560028 * days).rem_euclid(360.0);
+    let center = 1.9148 * sin(anomaly) + 0.02 * sin(2.0 * anomaly) + 0.0003 * sin(3.0 * anomaly);
+    let ecliptic_longitude = (anomaly + center + 180.0 + 102.9372).rem_euclid(360.0);
+    let transit = days + 0.0053 * sin(anomaly) - 0.0069 * sin(2.0 * ecliptic_longitude);
+
+    let declination = (sin(ecliptic_longitude) * sin(23.4397)).asin().to_degrees();
+    let cos_hour_angle =
+        (sin(-0.833) - sin(latitude) * sin(declination)) / (cos(latitude) * cos(declination));
+    if !(-1.0..=1.0).contains(&cos_hour_angle) {
+        return None;
+    }
+    let hour_angle = cos_hour_angle.acos().to_degrees();
+
+    // the days are counted from 2000-01-01 12:00 UTC, which is the unix timestamp 946728000
+    let local_time = |days: f64| -> Option<NaiveTime> {
+        let timestamp = 946_728_000 + (days * 86_400.0).round() as i64;
+        Some(Local.timestamp_opt(timestamp, 0).single()?.time())
+    };
+
+    Some((
+        local_time(transit - hour_angle / 360.0)?,
+        local_time(transit + hour_angle / 360.0)?,
+    ))
+}
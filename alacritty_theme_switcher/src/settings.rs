@@ -0,0 +1,120 @@
+use anyhow::{anyhow, Result};
+use clap::ValueEnum;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+use toml_edit::{DocumentMut, Item, TableLike};
+
+use crate::{
+    keys::{Action, Focus, Key, Keymap},
+    osc::PreviewMode,
+    schedule::Schedule,
+};
+
+// settings of the theme switcher itself, read from `$XDG_CONFIG_HOME/alacritty_theme_switcher/config.toml`
+#[derive(Debug, Default)]
+pub struct Settings {
+    pub theme_dirs: Vec<PathBuf>,     // Directories to scan for themes.
+    pub preview: Option<PreviewMode>, // How the selected theme is previewed.
+    pub keys: Keymap,                 // Default key bindings with the ones of `[keys]`.
+    pub schedule: Option<Schedule>,   // Themes to apply at given times of the day.
+}
+
+impl Settings {
+    pub fn load() -> Result<Settings> {
+        let path = xdg::BaseDirectories::with_prefix("alacritty_theme_switcher")
+            .ok()
+            .and_then(|xdg| xdg.find_config_file("config.toml"));
+
+        match path {
+            Some(path) => Settings::read(&path),
+            None => Ok(Settings::default()),
+        }
+    }
+
+    fn read(path: &Path) -> Result<Settings> {
+        let contents = fs::read_to_string(path)?;
+        let doc: DocumentMut = contents
+            .parse()
+            .map_err(|e| anyhow!("Failed to parse {}: {e}", path.display()))?;
+
+        // relative directories are relative to the settings file
+        let base = path.parent().unwrap_or(Path::new(""));
+
+        let theme_dirs = doc
+            .get("theme_dirs")
+            .and_then(Item::as_array)
+            .map(|dirs| {
+                dirs.iter()
+                    .filter_map(|d| d.as_str())
+                    .map(|d| base.join(expand_home(d)))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let preview = match doc.get("preview").and_then(Item::as_str) {
+            Some(preview) => Some(
+                PreviewMode::from_str(preview, true)
+                    .map_err(|e| anyhow!("Invalid preview in {}: {e}", path.display()))?,
+            ),
+            None => None,
+        };
+
+        let mut keys = Keymap::default();
+        if let Some(table) = doc.get("keys").and_then(Item::as_table_like) {
+            read_keys(table, Focus::Filter, &mut keys)
+                .map_err(|e| anyhow!("Invalid key binding in {}: {e}", path.display()))?;
+
+            if let Some(list) = table.get("list").and_then(Item::as_table_like) {
+                read_keys(list, Focus::List, &mut keys)
+                    .map_err(|e| anyhow!("Invalid key binding in {}: {e}", path.display()))?;
+            }
+        }
+
+        let schedule = match doc.get("schedule").and_then(Item::as_table_like) {
+            Some(table) => Some(
+                Schedule::parse(table)
+                    .map_err(|e| anyhow!("Invalid schedule in {}: {e}", path.display()))?,
+            ),
+            None => None,
+        };
+
+        Ok(Settings {
+            theme_dirs,
+            preview,
+            keys,
+            schedule,
+        })
+    }
+}
+
+// reads `key = "action"` entries, such as `ctrl-j = "select-next"`
+fn read_keys(table: &dyn TableLike, focus: Focus, keys: &mut Keymap) -> Result<()> {
+    for (key, action) in table.iter() {
+        // the `[keys.list]` table
+        let Some(action) = action.as_str() else {
+            continue;
+        };
+
+        let action = Action::from_str(action, true)
+            .map_err(|_| anyhow!("Unknown action `{action}` for `{key}`"))?;
+        keys.bind(focus, Key::parse(key)?, action);
+    }
+
+    Ok(())
+}
+
+// expands a leading `~`, `$HOME` or `${HOME}` to the home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    let rest = ["~", "$HOME", "${HOME}"]
+        .iter()
+        .find_map(|prefix| path.strip_prefix(prefix));
+
+    match (rest, env::var("HOME")) {
+        (Some(rest), Ok(home)) if rest.is_empty() || rest.starts_with('/') => {
+            PathBuf::from(format!("{home}{rest}"))
+        }
+        _ => PathBuf::from(path),
+    }
+}
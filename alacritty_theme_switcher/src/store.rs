@@ -0,0 +1,284 @@
+use anyhow::{anyhow, Result};
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    settings::{expand_home, Settings},
+    theme::{find_themes, FileFormat, Theme},
+};
+
+// environment variable listing the theme directories, separated like `$PATH`
+pub const THEMES_PATH_VAR: &str = "ALACRITTY_THEMES_PATH";
+
+// the theme directories are taken from the command line, the environment or the settings file,
+// in that order, and default to the `themes/themes` directory next to the config file
+pub fn find_theme_dirs(
+    requested: &[PathBuf],
+    settings: &Settings,
+    config_path: &Path,
+) -> Result<Vec<PathBuf>> {
+    // the imports written to the config must not depend on the working directory
+    let absolute = |dirs: &[PathBuf]| -> Result<Vec<PathBuf>> {
+        Ok(dirs
+            .iter()
+            .map(std::path::absolute)
+            .collect::<Result<_, _>>()?)
+    };
+
+    if !requested.is_empty() {
+        return absolute(requested);
+    }
+
+    if let Some(paths) = env::var_os(THEMES_PATH_VAR) {
+        let dirs: Vec<_> = env::split_paths(&paths)
+            .filter(|p| !p.as_os_str().is_empty())
+            .map(|p| expand_home(&p.to_string_lossy()))
+            .collect();
+        if !dirs.is_empty() {
+            return absolute(&dirs);
+        }
+    }
+
+    if !settings.theme_dirs.is_empty() {
+        return absolute(&settings.theme_dirs);
+    }
+
+    let config_dir = config_path.parent().unwrap_or(Path::new(""));
+    Ok(vec![config_dir.join("themes/themes")])
+}
+
+// scans the directories for themes of the given format, sorted alphabetically, which is also the
+// order of equally matching themes
+pub fn scan_themes(dirs: &[PathBuf], format: FileFormat) -> Vec<Theme> {
+    let mut themes = find_themes(dirs, format);
+    themes.sort_by(|a, b| a.name.cmp(&b.name));
+
+    themes
+}
+
+// the theme directories and the themes found in them
+#[derive(Debug, Default, Clone)]
+pub struct ThemeStore {
+    pub dirs: Vec<PathBuf>, // Directories to scan for themes.
+    pub format: FileFormat, // Format of the themes, the one of the config importing them.
+    pub themes: Vec<Theme>, // List of themes found in the theme directories.
+}
+
+impl ThemeStore {
+    pub fn new(dirs: Vec<PathBuf>, format: FileFormat) -> ThemeStore {
+        ThemeStore {
+            themes: scan_themes(&dirs, format),
+            dirs,
+            format,
+        }
+    }
+
+    // scans the directories again, to pick up the themes added or changed since
+    pub fn rescan(&mut self) {
+        self.themes = scan_themes(&self.dirs, self.format);
+    }
+
+    pub fn find(&self, name: &str) -> Option<&Theme> {
+        self.themes.iter().find(|t| t.name == name)
+    }
+
+    pub fn find_path(&self, path: &Path) -> Option<&Theme> {
+        self.themes.iter().find(|t| t.path == path)
+    }
+
+    // new themes are written as TOML, which the YAML configs can't import, so they would never
+    // show up in the list
+    pub fn check_writable(&self) -> Result<()> {
+        if self.format == FileFormat::Yaml {
+            return Err(anyhow!(
+                "New themes are written as TOML, which a YAML config can't import, see `migrate`"
+            ));
+        }
+
+        Ok(())
+    }
+
+    // returns whether an imported file is a theme, rather than some other included file
+    pub fn is_theme_import(&self, path: &Path) -> bool {
+        self.dirs.iter().any(|d| path.starts_with(d)) || self.find_path(path).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    const THEME: &str = "[colors.primary]\nbackground = \"#1d1f21\"\nforeground = \"#c5c8c6\"\n";
+
+    // creates a directory holding the given files
+    fn dir_with(files: &[&str]) -> TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for file in files {
+            let path = dir.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, THEME).unwrap();
+        }
+
+        dir
+    }
+
+    fn names(themes: &[Theme]) -> Vec<&str> {
+        themes.iter().map(|t| t.name.as_str()).collect()
+    }
+
+    #[test]
+    fn scan_nested_themes() {
+        let dir = dir_with(&[
+            "zenburn.toml",
+            "dark/dracula.toml",
+            "light/solarized/solarized_light.toml",
+            "README.md",
+            ".git/config.toml",
+        ]);
+
+        let themes = scan_themes(&[dir.path().to_path_buf()], FileFormat::Toml);
+
+        assert_eq!(names(&themes), ["dracula", "solarized_light", "zenburn"]);
+        assert_eq!(themes[0].path, dir.path().join("dark/dracula.toml"));
+        assert!(themes.iter().all(|t| t.problems.is_empty()));
+        assert!(themes.iter().all(|t| t.palette.is_some()));
+    }
+
+    #[test]
+    fn scan_missing_dir() {
+        let dir = dir_with(&[]);
+
+        assert!(scan_themes(&[dir.path().join("missing")], FileFormat::Toml).is_empty());
+    }
+
+    #[test]
+    fn label_duplicate_names() {
+        let first = dir_with(&["dracula.toml", "nord.toml"]);
+        let second = dir_with(&["custom/dracula.toml"]);
+        let custom = second.path().join("custom");
+
+        let themes = scan_themes(&[first.path().to_path_buf(), custom], FileFormat::Toml);
+        let first_label = first.path().file_name().unwrap().to_string_lossy();
+
+        assert_eq!(
+            names(&themes),
+            [
+                format!("dracula ({first_label})").as_str(),
+                "dracula (custom)",
+                "nord"
+            ]
+        );
+    }
+
+    #[test]
+    fn label_duplicate_names_in_subdirectories() {
+        let dir = dir_with(&[
+            "dark/nord.toml",
+            "light/nord.toml",
+            "a/themes/x.toml",
+            "b/themes/x.toml",
+        ]);
+
+        let themes = scan_themes(&[dir.path().to_path_buf()], FileFormat::Toml);
+
+        assert_eq!(
+            names(&themes),
+            [
+                "nord (dark)",
+                "nord (light)",
+                "x (a/themes)",
+                "x (b/themes)"
+            ]
+        );
+    }
+
+    #[test]
+    fn refuse_new_themes_for_yaml_configs() {
+        let dir = dir_with(&[]);
+
+        assert!(
+            ThemeStore::new(vec![dir.path().to_path_buf()], FileFormat::Toml)
+                .check_writable()
+                .is_ok()
+        );
+        assert!(
+            ThemeStore::new(vec![dir.path().to_path_buf()], FileFormat::Yaml)
+                .check_writable()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn scan_overlapping_dirs_once() {
+        let dir = dir_with(&["dark/dracula.toml"]);
+
+        let themes = scan_themes(
+            &[dir.path().to_path_buf(), dir.path().join("dark")],
+            FileFormat::Toml,
+        );
+
+        assert_eq!(names(&themes), ["dracula"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn scan_symlinked_theme_once() {
+        let dir = dir_with(&["dracula.toml"]);
+        std::os::unix::fs::symlink(
+            dir.path().join("dracula.toml"),
+            dir.path().join("link.toml"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            scan_themes(&[dir.path().to_path_buf()], FileFormat::Toml).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn report_broken_themes() {
+        let dir = dir_with(&[]);
+        fs::write(dir.path().join("broken.toml"), "colors:\n  primary:\n").unwrap();
+
+        let themes = scan_themes(&[dir.path().to_path_buf()], FileFormat::Toml);
+
+        assert_eq!(themes[0].palette, None);
+        assert_eq!(themes[0].problems.len(), 1);
+    }
+
+    #[test]
+    fn scan_yaml_themes() {
+        let dir = dir_with(&["nord.toml"]);
+        fs::write(
+            dir.path().join("dracula.yml"),
+            "colors:\n  primary:\n    background: '0x282a36'\n",
+        )
+        .unwrap();
+
+        let themes = scan_themes(&[dir.path().to_path_buf()], FileFormat::Yaml);
+
+        assert_eq!(names(&themes), ["dracula"]);
+        let background = themes[0].palette.as_ref().unwrap().background;
+        assert_eq!(
+            background.map(|c| c.to_string()).as_deref(),
+            Some("#282a36")
+        );
+        assert!(themes[0].problems.is_empty());
+    }
+
+    #[test]
+    fn theme_imports() {
+        let dir = dir_with(&["dracula.toml"]);
+        let store = ThemeStore::new(vec![dir.path().to_path_buf()], FileFormat::Toml);
+        let theme = store.find("dracula").unwrap();
+
+        assert!(store.is_theme_import(&theme.path));
+        assert!(store.is_theme_import(&dir.path().join("gone.toml")));
+        assert!(!store.is_theme_import(Path::new("/home/user/.config/alacritty/keys.toml")));
+    }
+}
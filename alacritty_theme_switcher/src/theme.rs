@@ -0,0 +1,244 @@
+use anyhow::{anyhow, Result};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    lint::{lint, Problem},
+    palette::{Palette, Rgb, LIGHT_LUMINANCE},
+    yaml,
+};
+
+// saturation under which a color is gray, and has no meaningful hue
+const GRAY_SATURATION: f64 = 0.05;
+
+// the format of config and theme files, alacritty read YAML files before version 0.13
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    #[default]
+    Toml,
+    Yaml,
+}
+
+impl FileFormat {
+    // guesses the format from the extension
+    pub fn of(path: &Path) -> Option<FileFormat> {
+        match path.extension()?.to_str()? {
+            "toml" => Some(FileFormat::Toml),
+            "yml" | "yaml" => Some(FileFormat::Yaml),
+            _ => None,
+        }
+    }
+}
+
+// reads a theme file as TOML, legacy YAML themes are converted
+pub fn read_theme(path: &Path) -> Result<String> {
+    let contents = fs::read_to_string(path).map_err(|e| anyhow!("Failed to read the file: {e}"))?;
+
+    match FileFormat::of(path) {
+        Some(FileFormat::Yaml) => Ok(yaml::to_toml(&contents)
+            .map_err(|e| anyhow!("Failed to parse the YAML file: {e}"))?
+            .to_string()),
+        _ => Ok(contents),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Brightness {
+    Light,
+    Dark,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    pub name: String,             // Name shown in the list, unique among the themes.
+    pub path: PathBuf,            // Path to the theme file.
+    pub source: PathBuf,          // Theme directory the file was found in.
+    pub palette: Option<Palette>, // Colors of the theme, if the file could be parsed.
+    pub problems: Vec<Problem>,   // Problems alacritty would report when importing the theme.
+}
+
+impl Theme {
+    // luminance of the primary background color
+    pub fn luminance(&self) -> Option<f64> {
+        self.palette
+            .as_ref()
+            .and_then(|p| p.background)
+            .map(|bg| bg.luminance())
+    }
+
+    // hue of the background, which tints most of the screen, or of the foreground when the
+    // background is gray
+    pub fn hue(&self) -> Option<f64> {
+        let palette = self.palette.as_ref()?;
+
+        [palette.background, palette.foreground]
+            .into_iter()
+            .flatten()
+            .map(Rgb::to_hsl)
+            .find(|(_, saturation, _)| *saturation >= GRAY_SATURATION)
+            .map(|(hue, _, _)| hue)
+    }
+
+    // lowest contrast between the background and the text colors
+    pub fn min_contrast(&self) -> Option<f64> {
+        self.palette
+            .as_ref()
+            .and_then(|p| p.min_contrast())
+            .map(|(contrast, _)| contrast)
+    }
+
+    pub fn brightness(&self) -> Option<Brightness> {
+        self.luminance().map(|l| {
+            if l > LIGHT_LUMINANCE {
+                Brightness::Light
+            } else {
+                Brightness::Dark
+            }
+        })
+    }
+}
+
+// splits the `light:` or `dark:` prefix from a filter, returning the brightness and the fuzzy pattern
+pub fn parse_filter(input: &str) -> (Option<Brightness>, &str) {
+    if let Some(rest) = input.strip_prefix("light:") {
+        (Some(Brightness::Light), rest.trim_start())
+    } else if let Some(rest) = input.strip_prefix("dark:") {
+        (Some(Brightness::Dark), rest.trim_start())
+    } else {
+        (None, input)
+    }
+}
+
+// returns the name of a theme, which is its file name without the extension
+pub fn theme_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+// returns the last `depth` directories holding the theme, the label used to tell apart themes
+// with the same name
+fn dir_label(path: &Path, depth: usize) -> String {
+    let dirs: Vec<_> = path.parent().into_iter().flat_map(Path::iter).collect();
+    let start = dirs.len().saturating_sub(depth);
+
+    dirs[start..]
+        .iter()
+        .collect::<PathBuf>()
+        .display()
+        .to_string()
+}
+
+// recursively scans the given directories for theme files of the given format, directories that
+// don't exist are skipped
+pub fn find_themes(dirs: &[PathBuf], format: FileFormat) -> Vec<Theme> {
+    let mut visited = HashSet::new();
+    let mut themes = vec![];
+
+    for dir in dirs {
+        let mut files = vec![];
+        walk_dir(dir, format, &mut visited, &mut files);
+
+        themes.extend(files.into_iter().map(|path| {
+            let (palette, problems) = match read_theme(&path) {
+                Ok(contents) => (Palette::parse(&contents).ok(), lint(&contents)),
+                Err(e) => {
+                    let problem = Problem {
+                        key: String::new(),
+                        message: e.to_string(),
+                    };
+                    (None, vec![problem])
+                }
+            };
+
+            Theme {
+                name: theme_name(&path),
+                path,
+                source: dir.clone(),
+                palette,
+                problems,
+            }
+        }));
+    }
+
+    // label the themes whose name is used more than once with the directories holding them, as
+    // many as needed to tell them apart, e.g. `dracula (dark)` and `dracula (light)`
+    let mut groups: HashMap<String, Vec<usize>> = HashMap::new();
+    for (i, theme) in themes.iter().enumerate() {
+        groups.entry(theme.name.clone()).or_default().push(i);
+    }
+
+    for (name, group) in groups.into_iter().filter(|(_, group)| group.len() > 1) {
+        let max_depth = group
+            .iter()
+            .map(|&i| themes[i].path.components().count())
+            .max()
+            .unwrap_or_default();
+
+        let mut depth = 1;
+        let labels = loop {
+            let labels: Vec<_> = group
+                .iter()
+                .map(|&i| dir_label(&themes[i].path, depth))
+                .collect();
+            if depth >= max_depth || labels.iter().collect::<HashSet<_>>().len() == labels.len() {
+                break labels;
+            }
+            depth += 1;
+        };
+
+        for (&i, label) in group.iter().zip(labels) {
+            themes[i].name = format!("{name} ({label})");
+        }
+    }
+
+    themes
+}
+
+fn walk_dir(
+    dir: &Path,
+    format: FileFormat,
+    visited: &mut HashSet<PathBuf>,
+    files: &mut Vec<PathBuf>,
+) {
+    // skip directories that were already scanned, either through overlapping roots or symlinks
+    let Ok(canonical) = dir.canonicalize() else {
+        return;
+    };
+    if !visited.insert(canonical) {
+        return;
+    }
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    let mut entries: Vec<_> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+    entries.sort();
+
+    for path in entries {
+        // skip hidden entries, such as the `.git` directory of a cloned theme repository
+        if path
+            .file_name()
+            .is_some_and(|n| n.to_string_lossy().starts_with('.'))
+        {
+            continue;
+        }
+
+        let Ok(metadata) = path.metadata() else {
+            continue;
+        };
+
+        if metadata.is_dir() {
+            walk_dir(&path, format, visited, files);
+        } else if metadata.is_file() && FileFormat::of(&path) == Some(format) {
+            // the same file can be reached through a symlink
+            if path.canonicalize().is_ok_and(|c| visited.insert(c)) {
+                files.push(path);
+            }
+        }
+    }
+}
@@ -0,0 +1,567 @@
+use alacritty_theme_switcher::{
+    keys::Focus,
+    matcher::SortMode,
+    palette::{Palette, Rgb, AA_CONTRAST, ANSI_NAMES},
+    theme::{parse_filter, Brightness},
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    symbols::border,
+    text::{Line, Span, Text},
+    widgets::{Block, List, ListDirection, Paragraph, StatefulWidget, Widget, Wrap},
+};
+
+use crate::ThemeChanger;
+
+// width of the preview pane, which fits two columns of colors
+const PREVIEW_WIDTH: u16 = 58;
+
+// narrowest list that still shows the names of the themes
+const MIN_LIST_WIDTH: u16 = 32;
+
+// narrowest sample pane worth showing, it is hidden in narrower terminals
+const MIN_SAMPLE_WIDTH: u16 = 40;
+
+impl Widget for &mut ThemeChanger {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let vertical = Layout::vertical([Constraint::Length(3), Constraint::Min(1)]);
+        let [input_area, messages_area] = vertical.areas(area);
+
+        // the list keeps a minimum width, taken from the preview if needed, and the sample pane
+        // only shows up when there is room left for it
+        let width = messages_area.width;
+        let show_sample = width >= MIN_LIST_WIDTH + PREVIEW_WIDTH + MIN_SAMPLE_WIDTH;
+        let list_width = if show_sample {
+            (width * 3 / 10).max(MIN_LIST_WIDTH)
+        } else {
+            width.saturating_sub(PREVIEW_WIDTH).max(MIN_LIST_WIDTH)
+        };
+        let horizontal = Layout::horizontal([
+            Constraint::Length(list_width),
+            Constraint::Length(PREVIEW_WIDTH),
+            Constraint::Fill(1),
+        ]);
+        let [left_area, right_area, sample_area] = horizontal.areas(messages_area);
+
+        // the editor takes the place of the filter and the themes list
+        if let Some(editor) = &mut self.editor {
+            editor.render(input_area, left_area, buf);
+        } else {
+            // the focused block has a yellow border
+            let border_style = |focus: Focus| {
+                if self.focus == focus {
+                    Style::default().fg(Color::Yellow)
+                } else {
+                    Style::default()
+                }
+            };
+
+            let mut input_block = Block::bordered()
+                .title("Filter")
+                .border_set(border::PLAIN)
+                .border_style(border_style(Focus::Filter));
+            if let Some(error) = &self.error {
+                input_block = input_block.title_bottom(Line::from(error.as_str()).red());
+            } else if let Some(notice) = &self.notice {
+                input_block = input_block.title_bottom(Line::from(notice.as_str()).yellow());
+            }
+
+            let input = Paragraph::new(self.input.as_str()).block(input_block);
+            input.render(input_area, buf);
+
+            // highlights the characters matched by the filter
+            let highlight = |name: &str, positions: &[usize]| -> Vec<Span> {
+                name.chars()
+                    .enumerate()
+                    .map(|(i, c)| {
+                        let span = Span::raw(c.to_string());
+                        if positions.contains(&i) {
+                            span.yellow().bold()
+                        } else {
+                            span
+                        }
+                    })
+                    .collect()
+            };
+
+            let items: Vec<_> = self
+                .matches
+                .iter()
+                .map(|m| {
+                    if self.show_profiles {
+                        // the profiles are followed by their theme, flagged when it is missing
+                        let profile = &self.profiles[m.index];
+                        let mut spans = vec!["  ".into()];
+                        spans.extend(highlight(&profile.name, &m.positions));
+                        spans.push(format!("  {}", profile.theme).dark_gray());
+                        if self.store.find(&profile.theme).is_none() {
+                            spans.push(" ⚠".red());
+                        }
+                        return Line::from(spans);
+                    }
+
+                    let theme = &self.store.themes[m.index];
+                    let marker = match theme.brightness() {
+                        Some(Brightness::Light) => "○ ",
+                        Some(Brightness::Dark) => "● ",
+                        None => "  ",
+                    };
+
+                    // mark the pinned themes
+                    let pin = if self.history.is_favourite(&theme.path) {
+                        "★ ".yellow()
+                    } else if self.history.pinned_recent().contains(&theme.path) {
+                        "↺ ".dark_gray()
+                    } else {
+                        "  ".into()
+                    };
+
+                    let mut spans = vec![pin, marker.dark_gray()];
+                    spans.extend(highlight(&theme.name, &m.positions));
+
+                    // flag the themes alacritty would complain about
+                    if !theme.problems.is_empty() {
+                        spans.push(" ⚠".red());
+                    }
+
+                    Line::from(spans)
+                })
+                .collect();
+
+            let msg = vec![
+                "Press ".into(),
+                "esc".bold(),
+                " to exit, ".into(),
+                "Enter".bold(),
+                " to apply, ".into(),
+                "Tab".bold(),
+                " to sort".into(),
+            ];
+
+            // the best matches come first while filtering, the sort mode orders the equal ones
+            let filtering = !parse_filter(&self.input).1.is_empty();
+            let title = match self.sort_mode {
+                _ if self.show_profiles => "Profiles".to_string(),
+                SortMode::Name | SortMode::NameReverse if filtering => {
+                    "Themes (best match)".to_string()
+                }
+                sort_mode if filtering => format!("Themes (best match, {})", sort_mode.title()),
+                sort_mode => format!("Themes ({})", sort_mode.title()),
+            };
+
+            let list = List::new(items)
+                .block(
+                    Block::bordered()
+                        .title(title)
+                        .title_bottom(msg)
+                        .border_set(border::PLAIN)
+                        .border_style(border_style(Focus::List)),
+                )
+                .highlight_style(Style::new().reversed())
+                .highlight_symbol("")
+                .repeat_highlight_symbol(true)
+                .direction(ListDirection::TopToBottom);
+
+            // used to scroll by a page, without the borders
+            self.page_size = left_area.height.saturating_sub(2).max(1) as usize;
+            StatefulWidget::render(list, left_area, buf, &mut self.state);
+        }
+
+        let palette = match (&self.editor, &self.preview) {
+            (Some(editor), _) => editor.palette.clone(),
+            (None, Some((_, Ok(palette)))) => palette.clone(),
+            _ => Palette::default(),
+        };
+
+        // draws a color as a swatch followed by its hex value, and optionally its contrast against
+        // the background, which is flagged when it doesn't pass WCAG AA
+        let swatch = |label: &str, color: Option<Rgb>, with_contrast: bool| -> Vec<Span> {
+            let (swatch, hex) = match color {
+                Some(color) => ("  ".bg(color), format!(" {color} ").into()),
+                None => ("  ".into(), " ------- ".into()),
+            };
+
+            let contrast = match palette.contrast(color) {
+                _ if !with_contrast => "      ".into(),
+                Some(c) if c < AA_CONTRAST => format!("{c:>4.1}! ").red().bold(),
+                Some(c) => format!("{c:>4.1}  ").into(),
+                None => "      ".into(),
+            };
+
+            vec![format!(" {label:<10}").into(), swatch, hex, contrast]
+        };
+
+        let mut lines = vec![
+            Line::from(
+                [
+                    swatch("foreground", palette.foreground, true),
+                    swatch("background", palette.background, false),
+                ]
+                .concat(),
+            ),
+            Line::from(
+                [
+                    swatch("cursor", palette.cursor, false),
+                    swatch("text", palette.cursor_text, false),
+                ]
+                .concat(),
+            ),
+            Line::from(
+                [
+                    swatch("selection", palette.selection_background, false),
+                    swatch("text", palette.selection_text, false),
+                ]
+                .concat(),
+            ),
+            Line::default(),
+            Line::from(vec![format!(" {:<28}", "normal").bold(), "bright".bold()]),
+        ];
+
+        for (i, name) in ANSI_NAMES.iter().enumerate() {
+            lines.push(Line::from(
+                [
+                    swatch(name, palette.normal[i], true),
+                    swatch(name, palette.bright[i], true),
+                ]
+                .concat(),
+            ));
+        }
+
+        // sample text using each color on top of the theme background
+        lines.push(Line::default());
+        for colors in [palette.normal, palette.bright] {
+            let spans = ANSI_NAMES
+                .iter()
+                .zip(colors)
+                .map(|(name, color)| match color {
+                    Some(color) => format!(" {name}").fg(color),
+                    None => format!(" {name}").into(),
+                });
+            lines.push(Line::from(spans.collect::<Vec<_>>()));
+        }
+
+        if let Some((contrast, name)) = palette.min_contrast() {
+            let verdict = if contrast < AA_CONTRAST {
+                "fails AA".red().bold()
+            } else {
+                "passes AA".green().bold()
+            };
+
+            lines.push(Line::default());
+            lines.push(Line::from(vec![
+                format!(" minimum contrast {contrast:.1} ({name}), ").into(),
+                verdict,
+            ]));
+        }
+
+        // the options set by the selected profile
+        if let Some(profile) = self.selected_profile().filter(|_| self.editor.is_none()) {
+            lines.push(Line::default());
+            lines.push(Line::from(format!(" profile {}", profile.name).bold()));
+            lines.extend(
+                profile
+                    .describe_overrides()
+                    .into_iter()
+                    .map(|o| Line::from(format!(" {o}"))),
+            );
+        }
+
+        if let (None, Some((_, Err(e)))) = (&self.editor, &self.preview) {
+            lines.push(Line::default());
+            lines.push(Line::from(format!(" Failed to read theme: {e}").red()));
+        }
+
+        let problems = self
+            .preview
+            .as_ref()
+            .filter(|_| self.editor.is_none())
+            .and_then(|(path, _)| self.store.find_path(path))
+            .map(|t| t.problems.as_slice())
+            .unwrap_or_default();
+        if !problems.is_empty() {
+            lines.push(Line::default());
+            lines.extend(problems.iter().map(|p| Line::from(format!(" ⚠ {p}").red())));
+        }
+
+        // paint the pane with the theme's own background and foreground
+        let mut style = Style::default();
+        if let Some(background) = palette.background {
+            style = style.bg(background.into());
+        }
+        if let Some(foreground) = palette.foreground {
+            style = style.fg(foreground.into());
+        }
+
+        let block = Block::bordered().title("Preview").border_set(border::PLAIN);
+
+        // long problem descriptions are wrapped
+        Paragraph::new(Text::from(lines))
+            .style(style)
+            .wrap(Wrap { trim: false })
+            .block(block)
+            .render(right_area, buf);
+
+        if !show_sample {
+            return;
+        }
+
+        let block = Block::bordered()
+            .title(format!("Sample: {}", self.sample.title()))
+            .border_set(border::PLAIN);
+
+        Paragraph::new(self.sample.lines(&palette))
+            .style(style)
+            .block(block)
+            .render(sample_area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_theme_switcher::{
+        config::Config, profile::parse_profiles, store::ThemeStore, theme::FileFormat,
+    };
+    use insta::assert_snapshot;
+    use ratatui::{
+        backend::TestBackend,
+        crossterm::event::{KeyCode, KeyEvent},
+        Terminal,
+    };
+    use std::fs;
+    use tempfile::TempDir;
+
+    use crate::{editor::Editor, sample::Sample};
+
+    const DRACULA: &str = r##"[colors.primary]
+background = "#282a36"
+foreground = "#f8f8f2"
+
+[colors.normal]
+black = "#21222c"
+red = "#ff5555"
+green = "#50fa7b"
+yellow = "#f1fa8c"
+blue = "#bd93f9"
+magenta = "#ff79c6"
+cyan = "#8be9fd"
+white = "#f8f8f2"
+
+[colors.bright]
+black = "#6272a4"
+red = "#ff6e6e"
+green = "#69ff94"
+yellow = "#ffffa5"
+blue = "#d6acff"
+magenta = "#ff92df"
+cyan = "#a4ffff"
+white = "#ffffff"
+"##;
+
+    const SOLARIZED_LIGHT: &str = r##"[colors.primary]
+background = "#fdf6e3"
+foreground = "#657b83"
+
+[colors.normal]
+black = "#073642"
+red = "#dc322f"
+green = "#859900"
+yellow = "#b58900"
+blue = "#268bd2"
+magenta = "#d33682"
+cyan = "#2aa198"
+white = "#eee8d5"
+"##;
+
+    const MISSPELLED: &str = r##"[colors.primary]
+background = "#1d1f21"
+forground = "#c5c8c6"
+"##;
+
+    // an app showing the themes written to a temporary directory, with the first one selected
+    fn app() -> (TempDir, ThemeChanger) {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, contents) in [
+            ("dracula", DRACULA),
+            ("solarized_light", SOLARIZED_LIGHT),
+            ("misspelled", MISSPELLED),
+        ] {
+            fs::write(dir.path().join(format!("{name}.toml")), contents).unwrap();
+        }
+
+        let mut app = ThemeChanger {
+            store: ThemeStore::new(vec![dir.path().to_path_buf()], FileFormat::Toml),
+            ..ThemeChanger::default()
+        };
+        app.update_matches();
+        app.state.select_first();
+
+        (dir, app)
+    }
+
+    fn render(app: &mut ThemeChanger) -> Terminal<TestBackend> {
+        render_width(app, 160)
+    }
+
+    fn render_width(app: &mut ThemeChanger, width: u16) -> Terminal<TestBackend> {
+        let mut terminal = Terminal::new(TestBackend::new(width, 26)).unwrap();
+        app.update_preview();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+
+        terminal
+    }
+
+    #[test]
+    fn render_themes() {
+        let (_dir, mut app) = app();
+
+        let terminal = render(&mut app);
+
+        assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn render_narrow_terminal() {
+        let (_dir, mut app) = app();
+
+        // the sample pane is hidden, and the list keeps its minimum width
+        let terminal = render_width(&mut app, 80);
+
+        assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn render_filtered_themes() {
+        let (_dir, mut app) = app();
+        app.input = "sol".to_string();
+        app.character_index = 3;
+        app.update_matches();
+
+        let mut terminal = render(&mut app);
+
+        assert_snapshot!(terminal.backend());
+        // the cursor is in the filter, after the input
+        terminal.backend_mut().assert_cursor_position((4, 1));
+        // the matched characters are highlighted
+        let cell = &terminal.backend().buffer()[(6, 4)];
+        assert_eq!((cell.symbol(), cell.fg), ("s", Color::Yellow));
+    }
+
+    #[test]
+    fn render_pinned_themes_by_contrast() {
+        let (_dir, mut app) = app();
+        let solarized = app.store.find("solarized_light").unwrap().path.clone();
+        app.history.toggle_favourite(&solarized);
+        app.sort_mode = SortMode::Contrast;
+        app.update_matches();
+
+        let terminal = render(&mut app);
+
+        assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn render_theme_problems() {
+        let (_dir, mut app) = app();
+        app.state.select(Some(1));
+
+        let terminal = render(&mut app);
+
+        assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn render_samples() {
+        let (_dir, mut app) = app();
+
+        for name in [
+            "sample_shell",
+            "sample_code",
+            "sample_diff",
+            "sample_listing",
+        ] {
+            let terminal = render(&mut app);
+            assert_snapshot!(name, terminal.backend());
+            app.sample = app.sample.next();
+        }
+        assert_eq!(app.sample, Sample::Shell);
+
+        // the sample uses the colors of the theme
+        let terminal = render(&mut app);
+        let cell = &terminal.backend().buffer()[(107, 8)];
+        assert_eq!(
+            (cell.symbol(), cell.fg, cell.bg),
+            (
+                "e",
+                Color::Rgb(0xff, 0x55, 0x55),
+                Color::Rgb(0x28, 0x2a, 0x36)
+            )
+        );
+    }
+
+    #[test]
+    fn render_profiles() {
+        let (_dir, mut app) = app();
+        app.profiles = parse_profiles(
+            "[work]\ntheme = \"solarized_light\"\nfont.size = 13\n\n[retro]\ntheme = \"gruvbox\"\n",
+        )
+        .unwrap();
+        app.toggle_profiles();
+
+        let terminal = render(&mut app);
+
+        assert_snapshot!(terminal.backend());
+        assert_eq!(app.selected_theme().unwrap().name, "solarized_light");
+        assert_eq!(app.selected_overrides().len(), 1);
+    }
+
+    #[test]
+    fn notice_missing_profiles() {
+        let (_dir, mut app) = app();
+
+        app.toggle_profiles();
+
+        assert!(!app.show_profiles);
+        assert!(app.notice.is_some());
+        assert!(app.error.is_none());
+    }
+
+    #[test]
+    fn render_editor() {
+        let (_dir, mut app) = app();
+        app.editor = Some(Editor::new(app.selected_theme().unwrap()));
+
+        let terminal = render(&mut app);
+
+        assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn keep_notices_apart_from_errors() {
+        let (_dir, mut app) = app();
+        let config_dir = tempfile::tempdir().unwrap();
+        let config_path = config_dir.path().join("alacritty.toml");
+        fs::write(&config_path, "").unwrap();
+        app.config = Config::read(config_path.clone()).unwrap();
+        app.notice = Some("Failed to watch the themes".to_string());
+
+        // the theme is applied, and the app can exit with it, while the notice is shown
+        app.update_theme();
+        let terminal = render(&mut app);
+        assert!(terminal
+            .backend()
+            .to_string()
+            .contains("Failed to watch the themes"));
+        assert!(app.error.is_none());
+        assert!(app.apply_selection().is_ok());
+        assert!(fs::read_to_string(&config_path)
+            .unwrap()
+            .contains("dracula.toml"));
+
+        app.handle_key_event(KeyEvent::from(KeyCode::Char('x')))
+            .unwrap();
+        assert!(app.notice.is_none());
+    }
+}
@@ -0,0 +1,146 @@
+use anyhow::Result;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher as _};
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+};
+
+use crate::theme::FileFormat;
+
+// the files changed since the last look
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Changes {
+    pub themes: HashSet<PathBuf>, // Files and directories changed in the theme directories.
+    pub config: bool,             // Whether the config file changed.
+}
+
+// watches the theme directories and the config file with inotify, so the interface follows the
+// themes added, edited or removed while it is open
+#[derive(Debug)]
+pub struct Watcher {
+    dirs: Vec<PathBuf>,                              // The watched theme directories.
+    config: Vec<PathBuf>,                            // The config file, and the file it links to.
+    events: Receiver<notify::Result<notify::Event>>, // The events sent by the watcher.
+    _watcher: RecommendedWatcher,                    // The watcher, which stops when dropped.
+}
+
+impl Watcher {
+    pub fn new(dirs: &[PathBuf], config: &Path) -> Result<Watcher> {
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+
+        // editors, like `write_atomic`, often replace the config rather than writing it, so its
+        // directory is watched, along with the one of the dotfile it links to
+        let mut config_paths = vec![config.to_path_buf()];
+        if let Ok(target) = fs::canonicalize(config) {
+            if target != config {
+                config_paths.push(target);
+            }
+        }
+        for path in &config_paths {
+            if let Some(dir) = path.parent().filter(|d| d.is_dir()) {
+                watcher.watch(dir, RecursiveMode::NonRecursive)?;
+            }
+        }
+
+        // after the config, so a theme directory holding the config is still watched recursively
+        let dirs: Vec<_> = dirs.iter().filter(|d| d.is_dir()).cloned().collect();
+        for dir in &dirs {
+            watcher.watch(dir, RecursiveMode::Recursive)?;
+        }
+
+        Ok(Watcher {
+            dirs,
+            config: config_paths,
+            events,
+            _watcher: watcher,
+        })
+    }
+
+    // returns the changes since the last call, without waiting
+    pub fn changes(&self) -> Changes {
+        let mut changes = Changes::default();
+
+        for event in self.events.try_iter().flatten() {
+            // reading the files, which scanning does, isn't a change
+            if matches!(event.kind, EventKind::Access(_)) {
+                continue;
+            }
+
+            for path in event.paths {
+                if self.config.contains(&path) {
+                    changes.config = true;
+                } else if self.dirs.iter().any(|d| path.starts_with(d))
+                    && (FileFormat::of(&path).is_some() || path.extension().is_none())
+                {
+                    // theme files, and directories, which may hold themes, skipping the backups
+                    // and temporary files written next to a config kept among the themes
+                    changes.themes.insert(path);
+                }
+            }
+        }
+
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        thread,
+        time::{Duration, Instant},
+    };
+
+    // waits for the watcher to report a change, events arrive from another thread
+    fn wait_for_changes(watcher: &Watcher) -> Changes {
+        let start = Instant::now();
+        let mut changes = Changes::default();
+
+        while start.elapsed() < Duration::from_secs(5) {
+            let new = watcher.changes();
+            changes.themes.extend(new.themes);
+            changes.config |= new.config;
+            if changes != Changes::default() {
+                // let the rest of the events of the write arrive
+                thread::sleep(Duration::from_millis(100));
+                let rest = watcher.changes();
+                changes.themes.extend(rest.themes);
+                changes.config |= rest.config;
+                break;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+
+        changes
+    }
+
+    #[test]
+    fn watch_themes_and_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let themes = dir.path().join("themes");
+        let config = dir.path().join("alacritty.toml");
+        fs::create_dir_all(themes.join("dark")).unwrap();
+        fs::write(&config, "").unwrap();
+
+        let watcher = Watcher::new(std::slice::from_ref(&themes), &config).unwrap();
+
+        fs::write(themes.join("dark/dracula.toml"), "").unwrap();
+        fs::write(themes.join("README.md"), "").unwrap();
+        let changes = wait_for_changes(&watcher);
+        assert_eq!(
+            changes.themes,
+            HashSet::from([themes.join("dark/dracula.toml")])
+        );
+        assert!(!changes.config);
+
+        // the other files next to the config are ignored
+        fs::write(dir.path().join("alacritty.toml.bak"), "").unwrap();
+        fs::write(&config, "[font]\nsize = 11\n").unwrap();
+        let changes = wait_for_changes(&watcher);
+        assert!(changes.themes.is_empty());
+        assert!(changes.config);
+    }
+}
@@ -0,0 +1,468 @@
+use anyhow::{anyhow, Result};
+use serde_yaml::Value;
+use toml_edit::{Array, ArrayOfTables, DocumentMut, InlineTable, Item, Table};
+
+// options moved to another table since the YAML configs, in the layout of alacritty 0.14, which
+// the rest of the tool writes: 0.13 moved the bindings, and 0.14 moved `import`, the other general
+// options and `shell`, so a migrated config needs alacritty 0.14 or later
+// https://github.com/alacritty/alacritty/blob/v0.14.0/CHANGELOG.md
+const MOVED_KEYS: [(&str, &str, &str); 8] = [
+    ("import", "general", "import"),
+    ("working_directory", "general", "working_directory"),
+    ("live_config_reload", "general", "live_config_reload"),
+    ("ipc_socket", "general", "ipc_socket"),
+    ("shell", "terminal", "shell"),
+    ("key_bindings", "keyboard", "bindings"),
+    ("mouse_bindings", "mouse", "bindings"),
+    (
+        "draw_bold_text_with_bright_colors",
+        "colors",
+        "draw_bold_text_with_bright_colors",
+    ),
+];
+
+fn key(value: &Value) -> Result<String> {
+    match value {
+        Value::String(s) => Ok(s.clone()),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        _ => Err(anyhow!("Unsupported key {value:?}")),
+    }
+}
+
+// converts a scalar or a sequence, mappings become inline tables, `null` has no TOML equivalent
+fn to_value(value: &Value) -> Result<Option<toml_edit::Value>> {
+    let value = match value {
+        Value::Null => return Ok(None),
+        Value::Bool(b) => (*b).into(),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => i.into(),
+            None => n.as_f64().unwrap_or_default().into(),
+        },
+        Value::String(s) => s.as_str().into(),
+        Value::Sequence(values) => {
+            let mut array = Array::new();
+            for value in values {
+                if let Some(value) = to_value(value)? {
+                    array.push(value);
+                }
+            }
+            array.into()
+        }
+        Value::Mapping(mapping) => {
+            let mut table = InlineTable::new();
+            for (k, v) in mapping {
+                if let Some(v) = to_value(v)? {
+                    table.insert(&key(k)?, v);
+                }
+            }
+            table.into()
+        }
+        Value::Tagged(tagged) => return to_value(&tagged.value),
+    };
+
+    Ok(Some(value))
+}
+
+// converts a value to a table entry, mappings become tables and sequences of mappings arrays of
+// tables, such as `[[colors.indexed_colors]]`
+fn to_item(value: &Value) -> Result<Option<Item>> {
+    match value {
+        Value::Mapping(_) => Ok(Some(Item::Table(to_table(value)?))),
+        Value::Sequence(values) if !values.is_empty() && values.iter().all(Value::is_mapping) => {
+            let mut array = ArrayOfTables::new();
+            for value in values {
+                array.push(to_table(value)?);
+            }
+            Ok(Some(Item::ArrayOfTables(array)))
+        }
+        Value::Tagged(tagged) => to_item(&tagged.value),
+        _ => Ok(to_value(value)?.map(Item::Value)),
+    }
+}
+
+fn to_table(value: &Value) -> Result<Table> {
+    let mut table = Table::new();
+    // tables holding only other tables don't need a header
+    table.set_implicit(true);
+
+    if let Value::Mapping(mapping) = value {
+        for (k, v) in mapping {
+            if let Some(item) = to_item(v)? {
+                table.insert(&key(k)?, item);
+            }
+        }
+    }
+
+    Ok(table)
+}
+
+// converts a YAML config or theme to a TOML document with the same structure, comments are lost
+pub fn to_toml(contents: &str) -> Result<DocumentMut> {
+    let mut value: Value = serde_yaml::from_str(contents)?;
+    value.apply_merge()?;
+
+    match value {
+        Value::Mapping(_) => Ok(to_table(&value)?.into()),
+        Value::Null => Ok(DocumentMut::new()),
+        _ => Err(anyhow!("Expected a mapping at the top level")),
+    }
+}
+
+// converts a YAML config to the TOML config of alacritty 0.14, moving the options whose table
+// changed, and pointing the imports to the migrated `.toml` files
+pub fn migrate(contents: &str) -> Result<DocumentMut> {
+    let mut doc = to_toml(contents)?;
+
+    for (old, table, new) in MOVED_KEYS {
+        let Some(item) = doc.remove(old) else {
+            continue;
+        };
+
+        let mut implicit = Table::new();
+        implicit.set_implicit(true);
+        doc.entry(table)
+            .or_insert(Item::Table(implicit))
+            .as_table_like_mut()
+            .ok_or_else(|| anyhow!("`{table}` is not a table"))?
+            .insert(new, item);
+    }
+
+    let imports = doc
+        .get_mut("general")
+        .and_then(|general| general.get_mut("import"))
+        .and_then(Item::as_array_mut);
+    if let Some(imports) = imports {
+        for import in imports.iter_mut() {
+            if let Some(path) = import.as_str().and_then(toml_path) {
+                *import = path.into();
+            }
+        }
+    }
+
+    Ok(doc)
+}
+
+// returns the path of the migrated file of a YAML file
+pub fn toml_path(path: &str) -> Option<String> {
+    let stem = path
+        .strip_suffix(".yml")
+        .or_else(|| path.strip_suffix(".yaml"))?;
+
+    Some(format!("{stem}.toml"))
+}
+
+// returns the entries of the top-level `import` list of a YAML config
+pub fn imports(contents: &str) -> Vec<String> {
+    let value: Value = serde_yaml::from_str(contents).unwrap_or_default();
+
+    value
+        .get("import")
+        .and_then(Value::as_sequence)
+        .map(|imports| {
+            imports
+                .iter()
+                .filter_map(|i| i.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// the value of a `- entry` line of a block sequence, with its position in the line
+fn list_entry(line: &str) -> Option<(usize, usize, String)> {
+    let trimmed = line.trim_start();
+    let rest = trimmed.strip_prefix('-')?;
+    if !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+
+    let start = line.len() - rest.trim_start().len();
+    let rest = &line[start..];
+
+    let (end, value) = match rest.chars().next() {
+        Some(quote @ ('"' | '\'')) => {
+            let close = rest[1..].find(quote)? + 1;
+            (start + close + 1, rest[1..close].to_string())
+        }
+        _ => {
+            let value = rest.split(" #").next().unwrap_or_default().trim_end();
+            (start + value.len(), value.to_string())
+        }
+    };
+
+    Some((start, end, value))
+}
+
+// quotes a path for a YAML flow or block value
+fn quoted(path: &str) -> String {
+    format!("\"{}\"", path.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// the line and the byte index of the bracket closing the flow sequence of the `import:` line,
+// skipping the brackets in quoted entries and comments
+fn flow_end(lines: &[String], import: usize) -> Option<(usize, usize)> {
+    let mut depth = 0;
+    let mut quote = None;
+
+    for (index, line) in lines.iter().enumerate().skip(import) {
+        let start = if index == import { "import:".len() } else { 0 };
+        // whether a quoted entry can start, after `[`, `,` or the start of a line
+        let mut entry_start = true;
+        let mut escaped = false;
+
+        for (i, c) in line.char_indices().skip_while(|(i, _)| *i < start) {
+            if let Some(q) = quote {
+                match c {
+                    '\\' if q == '"' && !escaped => escaped = true,
+                    _ if c == q && !escaped => quote = None,
+                    _ => escaped = false,
+                }
+                continue;
+            }
+
+            match c {
+                '"' | '\'' if entry_start => quote = Some(c),
+                '#' if i == 0 || line[..i].ends_with([' ', '\t']) => break,
+                '[' => depth += 1,
+                ']' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some((index, i));
+                    }
+                }
+                _ => {}
+            }
+            entry_start = matches!(c, '[' | ',') || (entry_start && c.is_whitespace());
+        }
+    }
+
+    None
+}
+
+// replaces the theme entry of the `import` list of a YAML config, or adds one, keeping the rest of
+// the file as it is
+pub fn with_theme_import(
+    contents: &str,
+    is_theme: impl Fn(&str) -> bool,
+    theme: &str,
+) -> Result<String> {
+    let mut lines: Vec<String> = contents.lines().map(String::from).collect();
+    let entry = quoted(theme);
+
+    let Some(import) = lines.iter().position(|l| l.starts_with("import:")) else {
+        // the file has no imports yet
+        lines.insert(0, "import:".to_string());
+        lines.insert(1, format!("  - {entry}"));
+        return Ok(lines.join("\n") + "\n");
+    };
+
+    let inline = lines[import]["import:".len()..]
+        .split(" #")
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    if inline.starts_with('[') {
+        // a flow sequence, such as `import: [~/keys.yml, ~/themes/dracula.yml]`, is written again on
+        // one line, keeping what follows it, the comments inside a sequence spanning several lines
+        // are lost
+        let (last, close) = flow_end(&lines, import)
+            .ok_or_else(|| anyhow!("The `import` list of the config is never closed"))?;
+        let node = lines[import..=last].join("\n");
+        let node = &node[..node.len() - lines[last].len() + close + 1];
+        let rest = lines[last][close + 1..].to_string();
+
+        let mut entries = imports(node);
+        match entries.iter().position(|e| is_theme(e)) {
+            Some(index) => entries[index] = theme.to_string(),
+            None => entries.push(theme.to_string()),
+        }
+        let entries: Vec<_> = entries.iter().map(|e| quoted(e)).collect();
+        lines.splice(
+            import..=last,
+            [format!("import: [{}]{rest}", entries.join(", "))],
+        );
+    } else {
+        // the entries of a block sequence follow, along with blank lines and comments
+        let mut last = import;
+        let mut indent = "  ".to_string();
+        let mut replaced = false;
+
+        for (index, line) in lines.iter_mut().enumerate().skip(import + 1) {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+
+            let Some((start, end, value)) = list_entry(line) else {
+                break;
+            };
+            last = index;
+            indent = line[..line.len() - line.trim_start().len()].to_string();
+
+            if !replaced && is_theme(&value) {
+                *line = format!("{}{entry}{}", &line[..start], &line[end..]);
+                replaced = true;
+            }
+        }
+
+        if !replaced {
+            lines.insert(last + 1, format!("{indent}- {entry}"));
+        }
+    }
+
+    let mut result = lines.join("\n");
+    if contents.ends_with('\n') || contents.is_empty() {
+        result.push('\n');
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THEME: &str = "\
+# Colors (Dracula)
+colors:
+  primary:
+    background: '0x282a36'
+    foreground: '#f8f8f2'
+  normal:
+    black: '#21222c'
+  indexed_colors:
+    - { index: 16, color: '#ffb86c' }
+";
+
+    const CONFIG: &str = "\
+import:
+  # the colors
+  - ~/.config/alacritty/themes/dracula.yml
+  - \"~/.config/alacritty/keys.yml\" # bindings
+
+shell:
+  program: /bin/zsh
+live_config_reload: true
+key_bindings:
+  - { key: N, mods: Control, action: SpawnNewInstance }
+";
+
+    fn is_theme(entry: &str) -> bool {
+        entry.contains("/themes/")
+    }
+
+    #[test]
+    fn convert_theme() {
+        let doc = to_toml(THEME).unwrap();
+
+        assert_eq!(
+            doc.to_string(),
+            "\
+[colors.primary]
+background = \"0x282a36\"
+foreground = \"#f8f8f2\"
+
+[colors.normal]
+black = \"#21222c\"
+
+[[colors.indexed_colors]]
+index = 16
+color = \"#ffb86c\"
+"
+        );
+    }
+
+    #[test]
+    fn migrate_config() {
+        let doc = migrate(CONFIG).unwrap();
+
+        assert_eq!(
+            doc.to_string(),
+            "\
+[general]
+import = [\"~/.config/alacritty/themes/dracula.toml\", \"~/.config/alacritty/keys.toml\"]
+live_config_reload = true
+
+[terminal.shell]
+program = \"/bin/zsh\"
+
+[[keyboard.bindings]]
+key = \"N\"
+mods = \"Control\"
+action = \"SpawnNewInstance\"
+"
+        );
+    }
+
+    #[test]
+    fn replace_block_import() {
+        let config = with_theme_import(CONFIG, is_theme, "/themes/nord.yml").unwrap();
+
+        assert_eq!(
+            config,
+            CONFIG.replace(
+                "~/.config/alacritty/themes/dracula.yml",
+                "\"/themes/nord.yml\""
+            )
+        );
+        assert_eq!(
+            imports(&config),
+            ["/themes/nord.yml", "~/.config/alacritty/keys.yml"]
+        );
+    }
+
+    #[test]
+    fn add_block_import() {
+        let config = "import:\n    - ~/keys.yml\nshell: /bin/zsh\n";
+
+        assert_eq!(
+            with_theme_import(config, is_theme, "/themes/nord.yml").unwrap(),
+            "import:\n    - ~/keys.yml\n    - \"/themes/nord.yml\"\nshell: /bin/zsh\n"
+        );
+    }
+
+    #[test]
+    fn add_import_list() {
+        assert_eq!(
+            with_theme_import("shell: /bin/zsh\n", is_theme, "/themes/nord.yml").unwrap(),
+            "import:\n  - \"/themes/nord.yml\"\nshell: /bin/zsh\n"
+        );
+    }
+
+    #[test]
+    fn replace_flow_import() {
+        let config = "import: [~/keys.yml, '/themes/dracula.yml'] # keys first\n";
+
+        assert_eq!(
+            with_theme_import(config, is_theme, "/themes/nord.yml").unwrap(),
+            "import: [\"~/keys.yml\", \"/themes/nord.yml\"] # keys first\n"
+        );
+    }
+
+    #[test]
+    fn replace_multiline_flow_import() {
+        let config = "\
+import: [
+  ~/keys.yml, # bindings
+  '/themes/dracula.yml',
+  \"~/fonts [large].yml\"
+] # imports
+shell: /bin/zsh
+";
+
+        let config = with_theme_import(config, is_theme, "/themes/nord.yml").unwrap();
+
+        assert_eq!(
+            config,
+            "import: [\"~/keys.yml\", \"/themes/nord.yml\", \"~/fonts [large].yml\"] # imports\nshell: /bin/zsh\n"
+        );
+        assert!(serde_yaml::from_str::<Value>(&config).is_ok());
+    }
+
+    #[test]
+    fn reject_unclosed_flow_import() {
+        let config = "import: [\n  ~/keys.yml,\nshell: /bin/zsh\n";
+
+        assert!(with_theme_import(config, is_theme, "/themes/nord.yml").is_err());
+    }
+}